@@ -0,0 +1,60 @@
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String, Symbol, Vec};
+
+// -----------------------------------------------------------------------
+// External contract interfaces
+// -----------------------------------------------------------------------
+
+/// Subset of `PatientVitalsContract` used to sync goal progress.
+#[allow(dead_code)]
+#[contractclient(name = "PatientVitalsClient")]
+pub trait PatientVitalsInterface {
+    fn get_latest_vital(env: Env, patient_id: Address, vital_type: Symbol) -> Option<(u64, u32)>;
+}
+
+/// Mirror of `ClinicalGuidelineContract`'s evidence grading.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EvidenceLevel {
+    A,
+    B,
+    C,
+    D,
+}
+
+/// Mirror of `ClinicalGuidelineContract`'s recommendation strength.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecommendationStrength {
+    Strong,
+    Moderate,
+    Weak,
+}
+
+/// Mirror of `ClinicalGuidelineContract`'s recommendation record.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuidelineRecommendation {
+    pub guideline_id: String,
+    pub applicable: bool,
+    pub recommendation: String,
+    pub strength: RecommendationStrength,
+    pub evidence_level: EvidenceLevel,
+    pub alternative_options: Vec<String>,
+    pub reason: Option<String>,
+    pub matched_count: u32,
+}
+
+/// Subset of `ClinicalGuidelineContract` used to attach guidance to plans.
+#[allow(dead_code)]
+#[contractclient(name = "ClinicalGuidelineClient")]
+pub trait ClinicalGuidelineInterface {
+    fn evaluate_guideline(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        guideline_id: String,
+        patient_attributes: Vec<BytesN<32>>,
+        log: bool,
+        bypass_cache: bool,
+    ) -> GuidelineRecommendation;
+}
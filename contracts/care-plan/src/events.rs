@@ -0,0 +1,227 @@
+use soroban_sdk::{contractevent, Address, BytesN, String, Symbol};
+
+/// The contract was initialized with an administrator.
+#[contractevent]
+pub struct Initialized {
+    pub admin: Address,
+}
+
+/// The contract code was upgraded.
+#[contractevent]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+    pub version: u32,
+}
+
+/// A second provider co-signed a plan.
+#[contractevent]
+pub struct CarePlanCosigned {
+    pub care_plan_id: u64,
+    pub second_provider: Address,
+    pub cosigned_at: u64,
+}
+
+/// A plan passed its end date and was expired.
+#[contractevent]
+pub struct CarePlanExpired {
+    pub care_plan_id: u64,
+    pub expired_at: u64,
+}
+
+/// A plan's review is past due.
+#[contractevent]
+pub struct ReviewOverdue {
+    pub care_plan_id: u64,
+    pub next_review_date: u64,
+}
+
+/// A plan's coded conditions were replaced.
+#[contractevent]
+pub struct CodedConditionsSet {
+    pub care_plan_id: u64,
+    pub count: u32,
+}
+
+/// An intervention's effectiveness was rated.
+#[contractevent]
+pub struct InterventionRated {
+    pub intervention_id: u64,
+    pub rater: Address,
+    pub effectiveness: u32,
+}
+
+/// An intervention was deactivated.
+#[contractevent]
+pub struct InterventionDeactivated {
+    pub intervention_id: u64,
+    pub provider_id: Address,
+}
+
+/// A clinical guideline was attached to a plan.
+#[contractevent]
+pub struct GuidelineAttached {
+    pub care_plan_id: u64,
+    pub guideline_id: String,
+    pub applicable: bool,
+}
+
+/// A goal's prerequisite goals were replaced.
+#[contractevent]
+pub struct GoalDependenciesSet {
+    pub goal_id: u64,
+    pub count: u32,
+}
+
+/// A goal moved to another plan.
+#[contractevent]
+pub struct GoalMoved {
+    pub goal_id: u64,
+    pub source_plan_id: u64,
+    pub target_plan_id: u64,
+}
+
+/// A goal was linked to a patient-vitals metric.
+#[contractevent]
+pub struct GoalVitalLinked {
+    pub goal_id: u64,
+    pub metric: Symbol,
+}
+
+/// Progress was recorded on a goal from patient vitals.
+#[contractevent]
+pub struct GoalProgressSynced {
+    pub goal_id: u64,
+    pub metric: Symbol,
+    pub value: i64,
+}
+
+/// A goal's priority changed.
+#[contractevent]
+pub struct GoalPriorityChanged {
+    pub goal_id: u64,
+    pub previous: Symbol,
+    pub priority: Symbol,
+}
+
+/// A goal was voided.
+#[contractevent]
+pub struct GoalVoided {
+    pub goal_id: u64,
+    pub provider_id: Address,
+}
+
+/// A progress entry was superseded by a correction.
+#[contractevent]
+pub struct ProgressCorrected {
+    pub goal_id: u64,
+    pub index: u32,
+    pub correction_index: u32,
+    pub corrector: Address,
+}
+
+/// A goal's achievement was retracted.
+#[contractevent]
+pub struct GoalAchievementRetracted {
+    pub goal_id: u64,
+    pub provider_id: Address,
+}
+
+/// A comment was added to a goal.
+#[contractevent]
+pub struct GoalCommentAdded {
+    pub goal_id: u64,
+    pub index: u32,
+    pub author: Address,
+}
+
+/// A barrier was assigned to a care team member.
+#[contractevent]
+pub struct BarrierAssigned {
+    pub barrier_id: u64,
+    pub assignee: Address,
+}
+
+/// The patient consented to a plan.
+#[contractevent]
+pub struct ConsentRecorded {
+    pub care_plan_id: u64,
+    pub patient_id: Address,
+}
+
+/// The patient revoked consent to a plan.
+#[contractevent]
+pub struct ConsentRevoked {
+    pub care_plan_id: u64,
+    pub patient_id: Address,
+}
+
+/// A scheduled review moved to a new date.
+#[contractevent]
+pub struct ReviewRescheduled {
+    pub review_id: u64,
+    pub previous_date: u64,
+    pub new_date: u64,
+}
+
+/// A scheduled review was cancelled.
+#[contractevent]
+pub struct ReviewCancelled {
+    pub review_id: u64,
+    pub provider_id: Address,
+}
+
+/// A care team role's permissions were replaced.
+#[contractevent]
+pub struct RolePermissionsSet {
+    pub care_plan_id: u64,
+    pub role: Symbol,
+}
+
+/// A completion report was generated for a plan.
+#[contractevent]
+pub struct CompletionReportGenerated {
+    pub care_plan_id: u64,
+}
+
+/// A plan's emergency contact was set.
+#[contractevent]
+pub struct EmergencyContactSet {
+    pub care_plan_id: u64,
+    pub contact: Address,
+}
+
+/// A note was added to a plan.
+#[contractevent]
+pub struct PlanNoteAdded {
+    pub care_plan_id: u64,
+    pub index: u32,
+    pub author: Address,
+}
+
+/// A plan was imported from another deployment.
+#[contractevent]
+pub struct CarePlanImported {
+    pub care_plan_id: u64,
+    pub source_id: u64,
+    pub admin: Address,
+}
+
+/// A plan's records were migrated to the current layout.
+#[contractevent]
+pub struct PlanMigrated {
+    pub care_plan_id: u64,
+    pub migrated: u32,
+}
+
+/// A goal was migrated to the current layout.
+#[contractevent]
+pub struct GoalMigrated {
+    pub goal_id: u64,
+}
+
+/// A plan was archived.
+#[contractevent]
+pub struct CarePlanArchived {
+    pub care_plan_id: u64,
+    pub archived_by: Address,
+}
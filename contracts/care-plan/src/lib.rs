@@ -1,21 +1,198 @@
 #![no_std]
 
+mod clients;
+mod events;
 mod storage;
 mod types;
 
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractimpl, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Symbol,
+    TryFromVal, Val, Vec,
+};
+use clients::{ClinicalGuidelineClient, PatientVitalsClient};
+use events::*;
 use storage::*;
 use types::*;
 
+/// Version recorded when the contract is first initialized.
+const INITIAL_VERSION: u32 = 1;
+
+/// Largest supported decimal scale for numeric goal targets.
+const MAX_SCALE: u32 = 18;
+
+/// Most conditions or goal statements a single care plan may list.
+const MAX_PLAN_ENTRIES: u32 = 50;
+
+/// Most plan ids accepted by a single get_plans_batch call.
+const MAX_BATCH: u32 = 50;
+
+/// Goal limit per plan when none is configured.
+const DEFAULT_MAX_GOALS_PER_PLAN: u32 = 200;
+
+/// Seconds an achievement stays retractable when no window is configured.
+const DEFAULT_RETRACTION_WINDOW: u64 = 86_400;
+
 #[contract]
 pub struct CarePlanContract;
 
 #[contractimpl]
 impl CarePlanContract {
-    /// Create a new care plan for a patient.
+    /// Initialize the contract with an administrator. When
+    /// `single_active_plan` is set, a patient may hold only one Active plan
+    /// of each plan type.
+    pub fn initialize(env: Env, admin: Address, single_active_plan: bool) -> Result<(), Error> {
+        if has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        set_admin(&env, &admin);
+        set_version(&env, INITIAL_VERSION);
+        set_single_active_plan(&env, single_active_plan);
+
+        Initialized { admin }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the contract version; zero before initialization.
+    pub fn version(env: Env) -> u32 {
+        get_version(&env)
+    }
+
+    /// Replace the contract code, keeping its storage, and bump the version.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+
+        let version = get_version(&env) + 1;
+        set_version(&env, version);
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        ContractUpgraded {
+            new_wasm_hash,
+            version,
+        }
+        .publish(&env);
+
+        Ok(version)
+    }
+
+    /// Rewrite a goal stored in the original layout into the current one.
+    /// Returns false when the goal is already current.
+    pub fn migrate_goal(env: Env, admin: Address, goal_id: u64) -> Result<bool, Error> {
+        require_admin(&env, &admin)?;
+        migrate_goal_record(&env, goal_id)
+    }
+
+    /// Migrate every goal with an id in `start_id..=end_id`, skipping ids
+    /// that don't exist. Returns how many goals were rewritten.
+    pub fn migrate_goals_range(
+        env: Env,
+        admin: Address,
+        start_id: u64,
+        end_id: u64,
+    ) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        if start_id > end_id || end_id - start_id >= MAX_BATCH as u64 {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut migrated = 0u32;
+        for goal_id in start_id..=end_id {
+            match migrate_goal_record(&env, goal_id) {
+                Ok(true) => migrated += 1,
+                Ok(false) | Err(Error::GoalNotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Rewrite a plan and its goals, interventions, barriers and reviews from
+    /// the original layouts into the current ones. Returns how many records
+    /// were rewritten.
+    pub fn migrate_plan(env: Env, admin: Address, care_plan_id: u64) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        migrate_plan_records(&env, care_plan_id)
+    }
+
+    /// Migrate every plan with an id in `start_id..=end_id` as `migrate_plan`
+    /// does, skipping ids that don't exist. Returns how many records were
+    /// rewritten.
+    pub fn migrate_plans_range(
+        env: Env,
+        admin: Address,
+        start_id: u64,
+        end_id: u64,
+    ) -> Result<u32, Error> {
+        require_admin(&env, &admin)?;
+        if start_id > end_id || end_id - start_id >= MAX_BATCH as u64 {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut migrated = 0u32;
+        for care_plan_id in start_id..=end_id {
+            match migrate_plan_records(&env, care_plan_id) {
+                Ok(count) => migrated += count,
+                Err(Error::CarePlanNotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Require recorded patient consent before patient-authored actions.
+    pub fn set_consent_required(env: Env, admin: Address, required: bool) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_consent_required(&env, required);
+        Ok(())
+    }
+
+    /// Require a second provider to co-sign new plans before they become
+    /// Active.
+    pub fn set_cosign_required(env: Env, admin: Address, required: bool) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_cosign_required(&env, required);
+        Ok(())
+    }
+
+    /// Cap how many goals a single care plan may hold.
+    pub fn set_max_goals_per_plan(env: Env, admin: Address, max: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        if max == 0 {
+            return Err(Error::InvalidInput);
+        }
+        set_max_goals_per_plan(&env, max);
+        Ok(())
+    }
+
+    /// Set the clinical-guideline contract that `attach_guideline` trusts.
+    pub fn set_guideline_contract(
+        env: Env,
+        admin: Address,
+        contract: Address,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_guideline_contract(&env, &contract);
+        Ok(())
+    }
+
+    /// Set how many days past `next_review_date` a plan's review may run
+    /// before it counts as overdue. Plans can override this individually.
+    pub fn set_review_grace_days(env: Env, admin: Address, days: u32) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_review_grace_days(&env, days);
+        Ok(())
+    }
+
+    /// Create a new care plan for a patient. `end_date`, when set, must be
+    /// after `start_date`. When co-signing is required the plan starts out
+    /// PendingApproval. A repeated call by the same provider with the same
+    /// `idempotency_key` returns the plan created by the first call instead
+    /// of a new one; reusing the key for another patient is rejected.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_care_plan(
         env: Env,
         patient_id: Address,
@@ -25,39 +202,322 @@ impl CarePlanContract {
         goals: Vec<String>,
         start_date: u64,
         review_frequency_days: u32,
+        end_date: Option<u64>,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<u64, Error> {
         provider_id.require_auth();
 
-        let care_plan_id = next_care_plan_id(&env);
-        let next_review_date = start_date + (review_frequency_days as u64 * 86_400);
+        if let Some(existing) = idempotency_key
+            .as_ref()
+            .and_then(|key| load_idempotent_plan(&env, &provider_id, key))
+        {
+            // A key reused for another patient is a caller bug, not a retry
+            let other_patient =
+                load_care_plan(&env, existing).is_some_and(|plan| plan.patient_id != patient_id);
+            if other_patient {
+                return Err(Error::IdempotencyKeyConflict);
+            }
+            return Ok(existing);
+        }
 
-        let plan = CarePlan {
-            care_plan_id,
-            patient_id: patient_id.clone(),
-            provider_id: provider_id.clone(),
+        let care_plan_id = open_care_plan(
+            &env,
+            None,
+            patient_id,
+            provider_id.clone(),
             plan_type,
             conditions,
             goals,
             start_date,
             review_frequency_days,
-            status: CarePlanStatus::Active,
-            next_review_date,
-            last_review_date: None,
-            created_at: env.ledger().timestamp(),
-        };
+            end_date,
+        )?;
+        if let Some(key) = &idempotency_key {
+            save_idempotent_plan(&env, &provider_id, key, care_plan_id);
+        }
 
-        save_care_plan(&env, &plan);
-        add_patient_plan(&env, &patient_id, care_plan_id);
+        Ok(care_plan_id)
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "care_plan_created"),),
-            (care_plan_id, patient_id, provider_id),
+    /// Create a care plan whose id is derived from the patient, provider and
+    /// an off-chain `external_ref`, so callers know the id before the
+    /// transaction confirms. Derived ids have the top bit set and never
+    /// overlap counter-assigned ones; an id already in use is rejected.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_care_plan_with_id(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        plan_type: Symbol,
+        conditions: Vec<String>,
+        goals: Vec<String>,
+        start_date: u64,
+        review_frequency_days: u32,
+        end_date: Option<u64>,
+        external_ref: BytesN<32>,
+    ) -> Result<u64, Error> {
+        provider_id.require_auth();
+
+        let care_plan_id = derive_plan_id(&env, &patient_id, &provider_id, &external_ref);
+        // Archived plans keep their id even after the live record is purged
+        let taken = load_care_plan(&env, care_plan_id).is_some()
+            || load_archive(&env, care_plan_id).is_some();
+        if taken {
+            return Err(Error::PlanIdTaken);
+        }
+
+        open_care_plan(
+            &env,
+            Some(care_plan_id),
+            patient_id,
+            provider_id,
+            plan_type,
+            conditions,
+            goals,
+            start_date,
+            review_frequency_days,
+            end_date,
+        )
+    }
+
+    /// Co-sign a plan awaiting approval, making it Active. The co-signer must
+    /// be a care team member other than the plan's author or patient, and
+    /// hold the cosign_plan permission when the plan has a role matrix.
+    pub fn cosign_care_plan(
+        env: Env,
+        care_plan_id: u64,
+        second_provider: Address,
+    ) -> Result<(), Error> {
+        second_provider.require_auth();
+
+        let mut plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !matches!(plan.status, CarePlanStatus::PendingApproval) {
+            return Err(Error::PlanNotPending);
+        }
+        if second_provider == plan.provider_id
+            || second_provider == plan.patient_id
+            || !is_care_provider(&env, &plan, &second_provider)
+        {
+            return Err(Error::Unauthorized);
+        }
+        ensure_role_permits(&env, &plan, &second_provider, "cosign_plan")?;
+        // Another plan of this type may have become Active while this one waited
+        ensure_single_active_slot(&env, &plan.patient_id, &plan.plan_type, Some(care_plan_id))?;
+
+        let now = env.ledger().timestamp();
+        save_plan_cosignature(
+            &env,
+            care_plan_id,
+            &PlanCosignature {
+                cosigner: second_provider.clone(),
+                cosigned_at: now,
+            },
         );
 
-        Ok(care_plan_id)
+        plan.status = CarePlanStatus::Active;
+        save_care_plan(&env, &plan);
+        append_status_history(&env, care_plan_id, &plan.status);
+
+        CarePlanCosigned {
+            care_plan_id,
+            second_provider,
+            cosigned_at: now,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the co-signature that activated a plan, if any.
+    pub fn get_plan_cosignature(env: Env, care_plan_id: u64) -> Option<PlanCosignature> {
+        load_plan_cosignature(&env, care_plan_id)
+    }
+
+    /// Complete an Active plan whose end date has passed. Anyone may call this
+    /// as a keeper; returns whether the plan was closed.
+    pub fn check_plan_expiry(env: Env, care_plan_id: u64) -> Result<bool, Error> {
+        let mut plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+
+        let now = env.ledger().timestamp();
+        let expired = matches!(plan.status, CarePlanStatus::Active)
+            && plan.end_date.is_some_and(|end| end < now);
+        if !expired {
+            return Ok(false);
+        }
+
+        plan.status = CarePlanStatus::Completed;
+        save_care_plan(&env, &plan);
+        append_status_history(&env, care_plan_id, &plan.status);
+
+        CarePlanExpired {
+            care_plan_id,
+            expired_at: now,
+        }
+        .publish(&env);
+
+        Ok(true)
+    }
+
+    /// Override the review grace period for one plan. Only the plan's
+    /// coordinating provider may change it.
+    pub fn set_plan_review_grace(
+        env: Env,
+        care_plan_id: u64,
+        provider_id: Address,
+        days: u32,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if plan.provider_id != provider_id {
+            return Err(Error::Unauthorized);
+        }
+        set_plan_review_grace_days(&env, care_plan_id, days);
+        Ok(())
+    }
+
+    /// Grace days applied to a plan's reviews, after any per-plan override.
+    pub fn get_review_grace_days(env: Env, care_plan_id: u64) -> u32 {
+        get_review_grace_days(&env, care_plan_id)
+    }
+
+    /// Whether an Active plan has passed its next review date plus the
+    /// review grace period.
+    pub fn is_review_overdue(env: Env, care_plan_id: u64) -> bool {
+        load_care_plan(&env, care_plan_id).is_some_and(|plan| plan_review_overdue(&env, &plan))
+    }
+
+    /// Move an Active plan whose review is overdue to UnderReview. Anyone may
+    /// call this as a keeper; returns whether the plan was flagged, so
+    /// repeated calls emit `review_overdue` only once.
+    pub fn flag_overdue_review(env: Env, care_plan_id: u64) -> Result<bool, Error> {
+        let mut plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !plan_review_overdue(&env, &plan) {
+            return Ok(false);
+        }
+
+        plan.status = CarePlanStatus::UnderReview;
+        save_care_plan(&env, &plan);
+        append_status_history(&env, care_plan_id, &plan.status);
+
+        ReviewOverdue {
+            care_plan_id,
+            next_review_date: plan.next_review_date,
+        }
+        .publish(&env);
+
+        Ok(true)
+    }
+
+    /// Fetch several care plans in one call. Results follow the order of
+    /// `ids`, with `None` for ids that don't exist.
+    pub fn get_plans_batch(env: Env, ids: Vec<u64>) -> Result<Vec<Option<CarePlan>>, Error> {
+        if ids.len() > MAX_BATCH {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut plans = Vec::new(&env);
+        for id in ids.iter() {
+            plans.push_back(load_care_plan(&env, id));
+        }
+        Ok(plans)
+    }
+
+    /// List a provider's care plan ids, `limit` at a time from `start`. A limit
+    /// of zero returns every remaining id.
+    pub fn get_provider_plans(
+        env: Env,
+        provider_id: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        let ids = load_provider_plans(&env, &provider_id);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| {
+            page.push_back(id);
+            true
+        });
+        page
+    }
+
+    /// List ids of plans that list `condition`, `limit` at a time from
+    /// `start`. A limit of zero returns every remaining id.
+    pub fn list_plans_by_condition(
+        env: Env,
+        condition: String,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        let ids = load_condition_plans(&env, &condition);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| {
+            page.push_back(id);
+            true
+        });
+        page
+    }
+
+    /// List ids of plans coded with the condition `code`, `limit` at a time
+    /// from `start`. A limit of zero returns every remaining id.
+    pub fn list_plans_by_condition_code(
+        env: Env,
+        code: Symbol,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        let ids = load_condition_code_plans(&env, &code);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| {
+            page.push_back(id);
+            true
+        });
+        page
+    }
+
+    /// Replace the plan's coded conditions. The free-text `conditions` are
+    /// left as they are.
+    pub fn set_coded_conditions(
+        env: Env,
+        care_plan_id: u64,
+        provider_id: Address,
+        coded_conditions: Vec<Condition>,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if coded_conditions.len() > MAX_PLAN_ENTRIES {
+            return Err(Error::InvalidInput);
+        }
+
+        for old in plan.coded_conditions.iter() {
+            remove_condition_code_plan(&env, &old.code, care_plan_id);
+        }
+        for condition in coded_conditions.iter() {
+            add_condition_code_plan(&env, &condition.code, care_plan_id);
+        }
+
+        plan.coded_conditions = coded_conditions;
+        save_care_plan(&env, &plan);
+
+        CodedConditionsSet {
+            care_plan_id,
+            count: plan.coded_conditions.len(),
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the ordered history of status changes for a care plan.
+    pub fn get_status_history(env: Env, care_plan_id: u64) -> Vec<(CarePlanStatus, u64)> {
+        load_status_history(&env, care_plan_id)
     }
 
     /// Add a goal to an existing care plan.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_care_goal(
         env: Env,
         care_plan_id: u64,
@@ -66,12 +526,20 @@ impl CarePlanContract {
         target_value: Option<String>,
         target_date: u64,
         priority: Symbol,
+        derived_from_guideline: Option<String>,
     ) -> Result<u64, Error> {
         provider_id.require_auth();
 
         if load_care_plan(&env, care_plan_id).is_none() {
             return Err(Error::CarePlanNotFound);
         }
+        ensure_goal_capacity(&env, care_plan_id)?;
+        // Provenance must point at a guideline attached to this plan
+        if let Some(guideline_id) = &derived_from_guideline {
+            if !load_plan_guidelines(&env, care_plan_id).contains(guideline_id) {
+                return Err(Error::InvalidInput);
+            }
+        }
 
         let goal_id = next_goal_id(&env);
 
@@ -80,18 +548,27 @@ impl CarePlanContract {
             care_plan_id,
             description: goal_description,
             target_value,
+            target_number: None,
+            scale: 0,
+            unit: Symbol::new(&env, ""),
             target_date,
             priority,
             status: GoalStatus::Active,
-            progress_entries: Vec::new(&env),
+            progress_count: 0,
             achievement_date: None,
-            outcome_notes: None,
+            vital_contract: None,
+            vital_metric: None,
+            depends_on: Vec::new(&env),
+            voided: false,
+            void_reason: None,
+            derived_from_guideline,
             created_by: provider_id.clone(),
             created_at: env.ledger().timestamp(),
         };
 
         save_goal(&env, &goal);
         add_plan_goal(&env, care_plan_id, goal_id);
+        update_provider_goal_stats(&env, &provider_id, 0, 1);
 
         env.events().publish(
             (Symbol::new(&env, "goal_added"),),
@@ -101,7 +578,9 @@ impl CarePlanContract {
         Ok(goal_id)
     }
 
-    /// Add an intervention to a care plan.
+    /// Add an intervention to a care plan. `goal_id`, when set, must name a
+    /// goal in the same plan.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_intervention(
         env: Env,
         care_plan_id: u64,
@@ -110,11 +589,24 @@ impl CarePlanContract {
         description: String,
         frequency: String,
         responsible_party: Symbol,
+        assignee: Option<Address>,
+        goal_id: Option<u64>,
     ) -> Result<u64, Error> {
         provider_id.require_auth();
 
-        if load_care_plan(&env, care_plan_id).is_none() {
-            return Err(Error::CarePlanNotFound);
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        ensure_role_permits(&env, &plan, &provider_id, "add_intervention")?;
+        if let Some(member) = &assignee {
+            let team = load_care_team(&env, care_plan_id);
+            if !team.iter().any(|m| m.team_member == *member) {
+                return Err(Error::InvalidInput);
+            }
+        }
+        if let Some(id) = goal_id {
+            let goal = load_goal(&env, id).ok_or(Error::GoalNotFound)?;
+            if goal.care_plan_id != care_plan_id {
+                return Err(Error::InvalidInput);
+            }
         }
 
         let intervention_id = next_intervention_id(&env);
@@ -126,8 +618,11 @@ impl CarePlanContract {
             description,
             frequency,
             responsible_party,
+            assignee,
             assigned_by: provider_id.clone(),
             created_at: env.ledger().timestamp(),
+            active: true,
+            goal_id,
         };
 
         save_intervention(&env, &intervention);
@@ -141,309 +636,1835 @@ impl CarePlanContract {
         Ok(intervention_id)
     }
 
-    /// Record progress against a care goal.
-    pub fn record_goal_progress(
+    /// Rate how effective an intervention has been, from 1 to 5.
+    pub fn rate_intervention(
         env: Env,
-        goal_id: u64,
-        patient_id: Address,
-        current_value: String,
-        progress_note: String,
-        recorded_date: u64,
+        intervention_id: u64,
+        rater: Address,
+        effectiveness: u32,
+        note: String,
     ) -> Result<(), Error> {
-        patient_id.require_auth();
-
-        let mut goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
-
-        if matches!(goal.status, GoalStatus::Achieved) {
-            return Err(Error::GoalAlreadyAchieved);
+        rater.require_auth();
+
+        let intervention =
+            load_intervention(&env, intervention_id).ok_or(Error::InterventionNotFound)?;
+        let plan =
+            load_care_plan(&env, intervention.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if rater != plan.patient_id && !is_care_provider(&env, &plan, &rater) {
+            return Err(Error::Unauthorized);
         }
-        if matches!(goal.status, GoalStatus::Discontinued) {
-            return Err(Error::GoalDiscontinued);
+        if !(1..=5).contains(&effectiveness) {
+            return Err(Error::InvalidInput);
         }
 
-        let entry = ProgressEntry {
-            goal_id,
-            patient_id: patient_id.clone(),
-            current_value,
-            progress_note,
-            recorded_date,
+        let rating = InterventionRating {
+            rater: rater.clone(),
+            effectiveness,
+            note,
+            rated_at: env.ledger().timestamp(),
         };
+        add_intervention_rating(&env, intervention_id, &rating);
 
-        goal.progress_entries.push_back(entry);
-        save_goal(&env, &goal);
-
-        env.events().publish(
-            (Symbol::new(&env, "goal_progress_recorded"),),
-            (goal_id, patient_id),
-        );
+        InterventionRated {
+            intervention_id,
+            rater,
+            effectiveness,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    /// Mark a care goal as achieved.
-    pub fn mark_goal_achieved(
+    /// Get every rating recorded for an intervention.
+    pub fn get_intervention_ratings(env: Env, intervention_id: u64) -> Vec<InterventionRating> {
+        load_intervention_ratings(&env, intervention_id)
+    }
+
+    /// Average effectiveness of an intervention in hundredths (e.g. 350 for
+    /// 3.5), or None if it has not been rated.
+    pub fn get_intervention_effectiveness(env: Env, intervention_id: u64) -> Option<u32> {
+        let ratings = load_intervention_ratings(&env, intervention_id);
+        if ratings.is_empty() {
+            return None;
+        }
+        let total: u32 = ratings.iter().map(|r| r.effectiveness).sum();
+        Some(total * 100 / ratings.len())
+    }
+
+    /// List the interventions on a plan assigned to a specific team member.
+    pub fn list_interventions_for_assignee(
         env: Env,
-        goal_id: u64,
+        care_plan_id: u64,
+        assignee: Address,
+    ) -> Vec<Intervention> {
+        let mut assigned = Vec::new(&env);
+        for id in load_plan_interventions(&env, care_plan_id).iter() {
+            if let Some(i) = load_intervention(&env, id) {
+                if i.assignee.as_ref() == Some(&assignee) {
+                    assigned.push_back(i);
+                }
+            }
+        }
+        assigned
+    }
+
+    /// List the interventions working towards a goal.
+    pub fn list_interventions_for_goal(env: Env, goal_id: u64) -> Result<Vec<Intervention>, Error> {
+        let goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
+
+        let mut linked = Vec::new(&env);
+        for id in load_plan_interventions(&env, goal.care_plan_id).iter() {
+            if let Some(i) = load_intervention(&env, id) {
+                if i.goal_id == Some(goal_id) {
+                    linked.push_back(i);
+                }
+            }
+        }
+        Ok(linked)
+    }
+
+    /// Stop an intervention without removing it from the plan's history.
+    pub fn deactivate_intervention(
+        env: Env,
+        intervention_id: u64,
         provider_id: Address,
-        achievement_date: u64,
-        outcome_notes: String,
     ) -> Result<(), Error> {
         provider_id.require_auth();
 
-        let mut goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
-
-        if matches!(goal.status, GoalStatus::Achieved) {
-            return Err(Error::GoalAlreadyAchieved);
+        let mut intervention =
+            load_intervention(&env, intervention_id).ok_or(Error::InterventionNotFound)?;
+        let plan =
+            load_care_plan(&env, intervention.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
         }
-        if matches!(goal.status, GoalStatus::Discontinued) {
-            return Err(Error::GoalDiscontinued);
+        if !intervention.active {
+            return Err(Error::InterventionInactive);
         }
 
-        goal.status = GoalStatus::Achieved;
-        goal.achievement_date = Some(achievement_date);
-        goal.outcome_notes = Some(outcome_notes);
-
-        save_goal(&env, &goal);
+        intervention.active = false;
+        save_intervention(&env, &intervention);
 
-        env.events().publish(
-            (Symbol::new(&env, "goal_achieved"),),
-            (goal_id, provider_id),
-        );
+        InterventionDeactivated {
+            intervention_id,
+            provider_id,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    /// Add a barrier to a care plan.
-    pub fn add_barrier(
+    /// List a plan's interventions, `limit` at a time from `start`, skipping
+    /// deactivated ones when `only_active` is set. A limit of zero returns
+    /// every remaining intervention.
+    pub fn list_interventions(
         env: Env,
         care_plan_id: u64,
-        reporter: Address,
-        barrier_type: Symbol,
-        description: String,
-        identified_date: u64,
-    ) -> Result<u64, Error> {
-        reporter.require_auth();
+        only_active: bool,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Intervention> {
+        let ids = load_plan_interventions(&env, care_plan_id);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| match load_intervention(&env, id) {
+            Some(i) if i.active || !only_active => {
+                page.push_back(i);
+                true
+            }
+            _ => false,
+        });
+        page
+    }
 
-        if load_care_plan(&env, care_plan_id).is_none() {
-            return Err(Error::CarePlanNotFound);
+    /// Evaluate a clinical guideline for the plan's patient and link it to the
+    /// plan. When the guideline applies, its recommendation is recorded as an
+    /// intervention and the new intervention id is returned. The guideline
+    /// contract must be the one configured with `set_guideline_contract`.
+    pub fn attach_guideline(
+        env: Env,
+        care_plan_id: u64,
+        provider_id: Address,
+        guideline_contract: Address,
+        guideline_id: String,
+        patient_attributes: Vec<BytesN<32>>,
+    ) -> Result<Option<u64>, Error> {
+        provider_id.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        ensure_role_permits(&env, &plan, &provider_id, "add_intervention")?;
+        if load_guideline_contract(&env).as_ref() != Some(&guideline_contract) {
+            return Err(Error::UntrustedGuidelineContract);
         }
 
-        let barrier_id = next_barrier_id(&env);
+        let mut guideline_ids = load_plan_guidelines(&env, care_plan_id);
+        if guideline_ids.contains(&guideline_id) {
+            return Err(Error::GuidelineAlreadyAttached);
+        }
 
-        let barrier = Barrier {
-            barrier_id,
+        let guidelines = ClinicalGuidelineClient::new(&env, &guideline_contract);
+        let recommendation = guidelines.evaluate_guideline(
+            &plan.patient_id,
+            &provider_id,
+            &guideline_id,
+            &patient_attributes,
+            &true,
+            &false,
+        );
+
+        guideline_ids.push_back(guideline_id.clone());
+        save_plan_guidelines(&env, care_plan_id, &guideline_ids);
+        increment_guideline_ref_count(&env, &guideline_contract, &guideline_id);
+
+        let mut intervention_id = None;
+        if recommendation.applicable {
+            let id = next_intervention_id(&env);
+            let intervention = Intervention {
+                intervention_id: id,
+                care_plan_id,
+                intervention_type: Symbol::new(&env, "guideline"),
+                description: recommendation.recommendation,
+                frequency: String::from_str(&env, "As recommended"),
+                responsible_party: Symbol::new(&env, "provider"),
+                assignee: None,
+                assigned_by: provider_id.clone(),
+                created_at: env.ledger().timestamp(),
+                active: true,
+                goal_id: None,
+            };
+            save_intervention(&env, &intervention);
+            add_plan_intervention(&env, care_plan_id, id);
+            intervention_id = Some(id);
+        }
+
+        GuidelineAttached {
             care_plan_id,
-            reporter: reporter.clone(),
-            barrier_type,
-            description,
-            identified_date,
-            resolved: false,
-            resolution: None,
-            resolution_date: None,
-            resolved_by: None,
-        };
+            guideline_id,
+            applicable: recommendation.applicable,
+        }
+        .publish(&env);
 
-        save_barrier(&env, &barrier);
-        add_plan_barrier(&env, care_plan_id, barrier_id);
+        Ok(intervention_id)
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "barrier_added"),),
-            (care_plan_id, barrier_id),
-        );
+    /// List the clinical guideline ids attached to a care plan.
+    pub fn list_plan_guidelines(env: Env, care_plan_id: u64) -> Vec<String> {
+        load_plan_guidelines(&env, care_plan_id)
+    }
 
-        Ok(barrier_id)
+    /// Number of care plans, archived ones included, a guideline from the
+    /// given guideline contract is attached to.
+    pub fn guideline_reference_count(
+        env: Env,
+        guideline_contract: Address,
+        guideline_id: String,
+    ) -> u32 {
+        load_guideline_ref_count(&env, &guideline_contract, &guideline_id)
     }
 
-    /// Resolve a barrier.
-    pub fn resolve_barrier(
+    /// Record progress against a care goal. The recorder must be the patient
+    /// or a member of the plan's care team.
+    pub fn record_goal_progress(
         env: Env,
-        barrier_id: u64,
+        goal_id: u64,
+        recorded_by: Address,
+        current_value: String,
+        progress_note: String,
+        recorded_date: u64,
+    ) -> Result<(), Error> {
+        append_progress(
+            &env,
+            goal_id,
+            recorded_by,
+            current_value,
+            progress_note,
+            None,
+            recorded_date,
+        )
+    }
+
+    /// Set a fixed-point numeric target on a goal, e.g. 70 with scale 1 and
+    /// unit `pct` for 7.0%.
+    pub fn set_numeric_target(
+        env: Env,
+        goal_id: u64,
         provider_id: Address,
-        resolution: String,
-        resolution_date: u64,
+        target_number: i64,
+        scale: u32,
+        unit: Symbol,
     ) -> Result<(), Error> {
         provider_id.require_auth();
 
-        let mut barrier = load_barrier(&env, barrier_id).ok_or(Error::BarrierNotFound)?;
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if scale > MAX_SCALE {
+            return Err(Error::InvalidInput);
+        }
 
-        if barrier.resolved {
-            return Err(Error::BarrierAlreadyResolved);
+        goal.target_number = Some(target_number);
+        goal.scale = scale;
+        goal.unit = unit;
+        save_goal(&env, &goal);
+
+        Ok(())
+    }
+
+    /// Record a fixed-point numeric reading against a goal with a numeric
+    /// target. The value uses the goal's scale.
+    pub fn record_numeric_progress(
+        env: Env,
+        goal_id: u64,
+        recorded_by: Address,
+        value: i64,
+    ) -> Result<(), Error> {
+        let goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
+        append_progress(
+            &env,
+            goal_id,
+            recorded_by,
+            fixed_to_string(&env, value, goal.scale),
+            String::from_str(&env, ""),
+            Some(value),
+            env.ledger().timestamp(),
+        )
+    }
+
+    /// Set the goals that must be achieved before this goal can progress.
+    pub fn set_goal_dependencies(
+        env: Env,
+        goal_id: u64,
+        provider_id: Address,
+        deps: Vec<u64>,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
         }
 
-        barrier.resolved = true;
-        barrier.resolution = Some(resolution);
-        barrier.resolution_date = Some(resolution_date);
-        barrier.resolved_by = Some(provider_id.clone());
+        for dep_id in deps.iter() {
+            if dep_id == goal_id {
+                return Err(Error::InvalidDependency);
+            }
+            let dep = load_goal(&env, dep_id).ok_or(Error::GoalNotFound)?;
+            if dep.care_plan_id != goal.care_plan_id {
+                return Err(Error::InvalidDependency);
+            }
+        }
+        if dependencies_reach(&env, &deps, goal_id) {
+            return Err(Error::InvalidDependency);
+        }
 
-        save_barrier(&env, &barrier);
+        goal.depends_on = deps;
+        save_goal(&env, &goal);
 
-        env.events().publish(
-            (Symbol::new(&env, "barrier_resolved"),),
-            (barrier_id, provider_id),
-        );
+        GoalDependenciesSet {
+            goal_id,
+            count: goal.depends_on.len(),
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    /// Schedule a review for a care plan.
-    pub fn schedule_care_plan_review(
+    /// Move a goal to another care plan belonging to the same patient.
+    pub fn move_goal(
         env: Env,
-        care_plan_id: u64,
+        goal_id: u64,
         provider_id: Address,
-        review_date: u64,
-        review_type: Symbol,
-    ) -> Result<u64, Error> {
+        target_plan_id: u64,
+    ) -> Result<(), Error> {
         provider_id.require_auth();
 
-        if load_care_plan(&env, care_plan_id).is_none() {
-            return Err(Error::CarePlanNotFound);
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let source_plan_id = goal.care_plan_id;
+        if source_plan_id == target_plan_id {
+            return Err(Error::InvalidInput);
         }
 
-        let review_id = next_review_id(&env);
+        let source = load_care_plan(&env, source_plan_id).ok_or(Error::CarePlanNotFound)?;
+        let target = load_care_plan(&env, target_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if source.patient_id != target.patient_id
+            || !is_care_provider(&env, &source, &provider_id)
+            || !is_care_provider(&env, &target, &provider_id)
+        {
+            return Err(Error::Unauthorized);
+        }
 
-        let review = CareReview {
-            review_id,
-            care_plan_id,
-            scheduled_by: provider_id.clone(),
-            review_date,
-            review_type,
-            conducted: false,
-            review_notes_hash: None,
-            plan_modifications: Vec::new(&env),
-            continue_plan: true,
-            conducted_by: None,
-            conducted_at: None,
-        };
+        ensure_goal_capacity(&env, target_plan_id)?;
 
-        save_review(&env, &review);
-        add_plan_review(&env, care_plan_id, review_id);
+        // Dependencies are plan-scoped, so linked goals cannot be moved apart.
+        if !goal.depends_on.is_empty() {
+            return Err(Error::InvalidDependency);
+        }
+        for id in load_plan_goals(&env, source_plan_id).iter() {
+            if let Some(other) = load_goal(&env, id) {
+                if other.depends_on.contains(goal_id) {
+                    return Err(Error::InvalidDependency);
+                }
+            }
+        }
+        // Interventions stay with their plan, so neither can a targeted goal.
+        for id in load_plan_interventions(&env, source_plan_id).iter() {
+            if let Some(i) = load_intervention(&env, id) {
+                if i.goal_id == Some(goal_id) {
+                    return Err(Error::InvalidDependency);
+                }
+            }
+        }
 
-        env.events().publish(
-            (Symbol::new(&env, "review_scheduled"),),
-            (care_plan_id, review_id, review_date),
-        );
+        goal.care_plan_id = target_plan_id;
+        save_goal(&env, &goal);
+        remove_plan_goal(&env, source_plan_id, goal_id);
+        add_plan_goal(&env, target_plan_id, goal_id);
 
-        Ok(review_id)
+        GoalMoved {
+            goal_id,
+            source_plan_id,
+            target_plan_id,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    /// Conduct a previously scheduled care plan review.
-    pub fn conduct_care_plan_review(
+    /// Link a goal to a metric tracked by a deployed patient-vitals contract.
+    /// Only the plan's care providers may link a goal.
+    pub fn link_goal_to_vital(
         env: Env,
-        review_id: u64,
+        goal_id: u64,
         provider_id: Address,
-        review_notes_hash: BytesN<32>,
-        plan_modifications: Vec<String>,
-        continue_plan: bool,
+        vitals_contract: Address,
+        metric: Symbol,
     ) -> Result<(), Error> {
         provider_id.require_auth();
 
-        let mut review = load_review(&env, review_id).ok_or(Error::ReviewNotFound)?;
-
-        if review.conducted {
-            return Err(Error::ReviewAlreadyConducted);
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
         }
 
-        let conducted_at = env.ledger().timestamp();
+        goal.vital_contract = Some(vitals_contract);
+        goal.vital_metric = Some(metric.clone());
+        save_goal(&env, &goal);
+        add_vital_goal(&env, &plan.patient_id, &metric, goal_id);
 
-        review.conducted = true;
-        review.review_notes_hash = Some(review_notes_hash);
-        review.plan_modifications = plan_modifications;
-        review.continue_plan = continue_plan;
-        review.conducted_by = Some(provider_id.clone());
-        review.conducted_at = Some(conducted_at);
+        GoalVitalLinked { goal_id, metric }.publish(&env);
 
-        // Update the parent care plan's last/next review dates
-        if let Some(mut plan) = load_care_plan(&env, review.care_plan_id) {
-            plan.last_review_date = Some(conducted_at);
-            plan.next_review_date =
-                conducted_at + (plan.review_frequency_days as u64 * 86_400);
+        Ok(())
+    }
 
-            if !continue_plan {
-                plan.status = CarePlanStatus::Completed;
-            }
+    /// Record the latest linked vital reading as a progress entry on the goal.
+    pub fn sync_goal_from_vitals(env: Env, goal_id: u64) -> Result<(), Error> {
+        let mut goal = load_active_goal(&env, goal_id)?;
 
-            save_care_plan(&env, &plan);
+        if matches!(goal.status, GoalStatus::Achieved) {
+            return Err(Error::GoalAlreadyAchieved);
+        }
+        if matches!(goal.status, GoalStatus::Discontinued) {
+            return Err(Error::GoalDiscontinued);
         }
 
-        save_review(&env, &review);
+        ensure_dependencies_met(&env, &goal)?;
 
-        env.events().publish(
-            (Symbol::new(&env, "review_conducted"),),
-            (review_id, provider_id, continue_plan),
-        );
+        let (vitals_contract, metric) = match (&goal.vital_contract, &goal.vital_metric) {
+            (Some(c), Some(m)) => (c.clone(), m.clone()),
+            _ => return Err(Error::VitalLinkNotSet),
+        };
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        ensure_consent(&env, plan.care_plan_id)?;
+
+        let vitals = PatientVitalsClient::new(&env, &vitals_contract);
+        let (measured_at, value) = vitals
+            .get_latest_vital(&plan.patient_id, &metric)
+            .ok_or(Error::VitalReadingNotFound)?;
+
+        let entry = ProgressEntry {
+            goal_id,
+            patient_id: plan.patient_id.clone(),
+            recorded_by: vitals_contract,
+            current_value: fixed_to_string(&env, value as i64, 0),
+            numeric_value: Some(value as i64),
+            progress_note: String::from_str(&env, "Synced from patient vitals"),
+            recorded_date: measured_at,
+            superseded: false,
+            corrects: None,
+        };
+
+        push_progress_entry(&env, &mut goal, &entry);
+        save_goal(&env, &goal);
+
+        GoalProgressSynced {
+            goal_id,
+            metric,
+            value: value as i64,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    /// Assign a care team member to a care plan.
-    pub fn assign_care_team_member(
+    /// Append a progress entry to every open goal linked to one of the
+    /// given readings. Called by the vitals contract as readings are recorded;
+    /// goals on plans lacking required consent are skipped. Returns the number
+    /// of goals updated.
+    pub fn record_vital_progress(
         env: Env,
-        care_plan_id: u64,
-        coordinating_provider: Address,
-        team_member: Address,
-        role: Symbol,
-        responsibilities: Vec<String>,
+        vitals_contract: Address,
+        patient_id: Address,
+        measured_at: u64,
+        readings: Vec<(Symbol, i64)>,
+    ) -> u32 {
+        vitals_contract.require_auth();
+
+        let mut updated = 0;
+        for (metric, value) in readings.iter() {
+            for goal_id in load_vital_goals(&env, &patient_id, &metric).iter() {
+                let mut goal = match load_goal(&env, goal_id) {
+                    Some(goal) => goal,
+                    None => continue,
+                };
+                // The index is append-only, so skip goals re-linked elsewhere since
+                if !goal_is_open(&goal)
+                    || goal.vital_contract.as_ref() != Some(&vitals_contract)
+                    || goal.vital_metric.as_ref() != Some(&metric)
+                    || ensure_dependencies_met(&env, &goal).is_err()
+                    || ensure_consent(&env, goal.care_plan_id).is_err()
+                {
+                    continue;
+                }
+
+                let entry = ProgressEntry {
+                    goal_id,
+                    patient_id: patient_id.clone(),
+                    recorded_by: vitals_contract.clone(),
+                    current_value: fixed_to_string(&env, value, 0),
+                    numeric_value: Some(value),
+                    progress_note: String::from_str(&env, "Recorded from patient vitals"),
+                    recorded_date: measured_at,
+                    superseded: false,
+                    corrects: None,
+                };
+                push_progress_entry(&env, &mut goal, &entry);
+                save_goal(&env, &goal);
+                updated += 1;
+
+                GoalProgressSynced {
+                    goal_id,
+                    metric: metric.clone(),
+                    value,
+                }
+                .publish(&env);
+            }
+        }
+
+        updated
+    }
+
+    /// Mark a care goal as achieved.
+    pub fn mark_goal_achieved(
+        env: Env,
+        goal_id: u64,
+        provider_id: Address,
+        achievement_date: u64,
+        outcome_notes: String,
     ) -> Result<(), Error> {
-        coordinating_provider.require_auth();
+        provider_id.require_auth();
 
-        if load_care_plan(&env, care_plan_id).is_none() {
-            return Err(Error::CarePlanNotFound);
+        let mut goal = load_active_goal(&env, goal_id)?;
+
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
         }
+        ensure_role_permits(&env, &plan, &provider_id, "achieve_goal")?;
 
-        let mut team = load_care_team(&env, care_plan_id);
+        if matches!(goal.status, GoalStatus::Achieved) {
+            return Err(Error::GoalAlreadyAchieved);
+        }
+        if matches!(goal.status, GoalStatus::Discontinued) {
+            return Err(Error::GoalDiscontinued);
+        }
 
-        let member = CareTeamMember {
-            care_plan_id,
-            team_member: team_member.clone(),
-            role,
-            responsibilities,
-            assigned_by: coordinating_provider.clone(),
-            assigned_at: env.ledger().timestamp(),
-        };
+        goal.status = GoalStatus::Achieved;
+        goal.achievement_date = Some(achievement_date);
 
-        team.push_back(member);
-        save_care_team(&env, care_plan_id, &team);
+        // Outcome notes live in a side table so summary reads stay cheap.
+        save_goal(&env, &goal);
+        save_goal_outcome(&env, goal_id, achievement_date, &outcome_notes);
+        save_goal_achieved_at(&env, goal_id, env.ledger().timestamp());
+        update_provider_goal_stats(&env, &goal.created_by, 1, 0);
 
         env.events().publish(
-            (Symbol::new(&env, "team_member_assigned"),),
-            (care_plan_id, team_member),
+            (Symbol::new(&env, "goal_achieved"),),
+            (goal_id, provider_id),
         );
 
         Ok(())
     }
 
-    /// Get a summary of a care plan.
-    pub fn get_care_plan_summary(
+    /// Change a goal's priority to `high`, `medium` or `low`.
+    pub fn set_goal_priority(
         env: Env,
-        care_plan_id: u64,
-        requester: Address,
-    ) -> Result<CarePlanSummary, Error> {
-        requester.require_auth();
-
-        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        goal_id: u64,
+        provider_id: Address,
+        priority: Symbol,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
 
-        // Collect active goals
-        let goal_ids = load_plan_goals(&env, care_plan_id);
-        let mut active_goals: Vec<CareGoal> = Vec::new(&env);
-        for id in goal_ids.iter() {
-            if let Some(g) = load_goal(&env, id) {
-                if !matches!(g.status, GoalStatus::Achieved | GoalStatus::Discontinued) {
-                    active_goals.push_back(g);
-                }
-            }
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if priority != Symbol::new(&env, "high")
+            && priority != Symbol::new(&env, "medium")
+            && priority != Symbol::new(&env, "low")
+        {
+            return Err(Error::InvalidInput);
         }
 
-        // Collect interventions
-        let intervention_ids = load_plan_interventions(&env, care_plan_id);
-        let mut interventions: Vec<Intervention> = Vec::new(&env);
-        for id in intervention_ids.iter() {
-            if let Some(i) = load_intervention(&env, id) {
-                interventions.push_back(i);
+        let previous = goal.priority.clone();
+        goal.priority = priority.clone();
+        save_goal(&env, &goal);
+
+        GoalPriorityChanged {
+            goal_id,
+            previous,
+            priority,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Void a goal, keeping its record for audit instead of deleting it.
+    pub fn void_goal(
+        env: Env,
+        goal_id: u64,
+        provider_id: Address,
+        reason: String,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+
+        goal.voided = true;
+        goal.void_reason = Some(reason);
+        save_goal(&env, &goal);
+        // A voided goal was entered in error and no longer counts
+        let achieved = matches!(goal.status, GoalStatus::Achieved);
+        update_provider_goal_stats(&env, &goal.created_by, -(achieved as i32), -1);
+
+        GoalVoided {
+            goal_id,
+            provider_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get a goal. Voided goals are only returned when `include_voided` is set.
+    pub fn get_goal(env: Env, goal_id: u64, include_voided: bool) -> Result<CareGoal, Error> {
+        let goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
+        if goal.voided && !include_voided {
+            return Err(Error::GoalNotFound);
+        }
+        Ok(goal)
+    }
+
+    /// Get a non-voided goal together with the care plan that owns it.
+    pub fn get_goal_with_plan(env: Env, goal_id: u64) -> Result<(CareGoal, CarePlan), Error> {
+        let goal = Self::get_goal(env.clone(), goal_id, false)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        Ok((goal, plan))
+    }
+
+    /// List a plan's non-voided goals in `status`, `limit` at a time from
+    /// `start`. A limit of zero returns every remaining goal.
+    pub fn list_goals_by_status(
+        env: Env,
+        care_plan_id: u64,
+        status: GoalStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<CareGoal> {
+        let ids = load_plan_goals(&env, care_plan_id);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| match load_goal(&env, id) {
+            Some(g) if !g.voided && g.status == status => {
+                page.push_back(g);
+                true
+            }
+            _ => false,
+        });
+        page
+    }
+
+    /// Get the progress entries recorded against a non-voided goal.
+    pub fn get_goal_progress(env: Env, goal_id: u64) -> Result<Vec<ProgressEntry>, Error> {
+        let goal = Self::get_goal(env.clone(), goal_id, false)?;
+        Ok(load_goal_progress(&env, &goal))
+    }
+
+    /// Correct a progress entry by appending a new entry that references it.
+    /// The original is only flagged as superseded, never rewritten, so the
+    /// audit trail keeps both values. Returns the index of the correction.
+    pub fn correct_progress_entry(
+        env: Env,
+        goal_id: u64,
+        index: u32,
+        corrector: Address,
+        corrected_value: String,
+        reason: String,
+    ) -> Result<u32, Error> {
+        corrector.require_auth();
+
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+
+        let mut original = load_progress_entry(&env, goal_id, index).ok_or(Error::InvalidInput)?;
+        if original.recorded_by != corrector && !is_care_provider(&env, &plan, &corrector) {
+            return Err(Error::Unauthorized);
+        }
+        // Only the latest version of an entry can be corrected
+        if original.superseded {
+            return Err(Error::InvalidInput);
+        }
+
+        original.superseded = true;
+        save_progress_entry(&env, goal_id, index, &original);
+        let correction = ProgressEntry {
+            goal_id,
+            patient_id: plan.patient_id,
+            recorded_by: corrector.clone(),
+            current_value: corrected_value,
+            progress_note: reason,
+            numeric_value: None,
+            recorded_date: env.ledger().timestamp(),
+            superseded: false,
+            corrects: Some(index),
+        };
+        let correction_index = push_progress_entry(&env, &mut goal, &correction);
+        save_goal(&env, &goal);
+
+        ProgressCorrected {
+            goal_id,
+            index,
+            correction_index,
+            corrector,
+        }
+        .publish(&env);
+
+        Ok(correction_index)
+    }
+
+    /// Whether a goal's target date has passed without it being achieved or
+    /// discontinued.
+    pub fn is_goal_overdue(env: Env, goal_id: u64) -> bool {
+        match load_goal(&env, goal_id) {
+            Some(goal) => goal_is_overdue(&env, &goal),
+            None => false,
+        }
+    }
+
+    /// List the ids of overdue goals on a care plan.
+    pub fn list_overdue_goals(env: Env, care_plan_id: u64) -> Vec<u64> {
+        let mut overdue = Vec::new(&env);
+        for id in load_plan_goals(&env, care_plan_id).iter() {
+            if let Some(goal) = load_goal(&env, id) {
+                if goal_is_overdue(&env, &goal) {
+                    overdue.push_back(id);
+                }
+            }
+        }
+        overdue
+    }
+
+    /// Set how long after marking a goal achieved the achievement may still
+    /// be retracted.
+    pub fn set_retraction_window(env: Env, admin: Address, seconds: u64) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_retraction_window(&env, seconds);
+        Ok(())
+    }
+
+    /// Undo a mistaken `mark_goal_achieved`, returning the goal to Active.
+    /// Only allowed within the retraction window; the retracted outcome is
+    /// kept in the goal's retraction log.
+    pub fn retract_achievement(env: Env, goal_id: u64, provider_id: Address) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut goal = load_active_goal(&env, goal_id)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if !matches!(goal.status, GoalStatus::Achieved) {
+            return Err(Error::InvalidInput);
+        }
+
+        let now = env.ledger().timestamp();
+        let window = get_retraction_window(&env).unwrap_or(DEFAULT_RETRACTION_WINDOW);
+        let marked_at = load_goal_achieved_at(&env, goal_id).unwrap_or(0);
+        if now > marked_at.saturating_add(window) {
+            return Err(Error::RetractionWindowClosed);
+        }
+
+        let (achievement_date, outcome_notes) =
+            load_goal_outcome(&env, goal_id).ok_or(Error::InvalidInput)?;
+        append_goal_retraction(
+            &env,
+            goal_id,
+            &AchievementRetraction {
+                retracted_by: provider_id.clone(),
+                retracted_at: now,
+                achievement_date,
+                outcome_notes,
+            },
+        );
+        remove_goal_outcome(&env, goal_id);
+
+        goal.status = GoalStatus::Active;
+        goal.achievement_date = None;
+        save_goal(&env, &goal);
+        update_provider_goal_stats(&env, &goal.created_by, -1, 0);
+
+        GoalAchievementRetracted {
+            goal_id,
+            provider_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the audit log of retracted achievements for a goal.
+    pub fn get_goal_retractions(env: Env, goal_id: u64) -> Vec<AchievementRetraction> {
+        load_goal_retractions(&env, goal_id)
+    }
+
+    /// Goals created by `provider_id` that were achieved, out of all goals
+    /// they created, as `(achieved, total)`. Voided goals are excluded.
+    pub fn provider_achievement_rate(env: Env, provider_id: Address) -> (u32, u32) {
+        load_provider_goal_stats(&env, &provider_id)
+    }
+
+    /// Get the achievement date and outcome notes recorded for a goal.
+    pub fn get_goal_outcome(env: Env, goal_id: u64) -> Option<(u64, String)> {
+        load_goal_outcome(&env, goal_id)
+    }
+
+    /// Append a comment to a goal's discussion thread and return its index.
+    /// Only the plan's providers may comment.
+    pub fn add_goal_comment(
+        env: Env,
+        goal_id: u64,
+        author: Address,
+        comment: String,
+        timestamp: u64,
+    ) -> Result<u32, Error> {
+        author.require_auth();
+
+        let goal = load_goal(&env, goal_id).ok_or(Error::GoalNotFound)?;
+        let plan = load_care_plan(&env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &author) {
+            return Err(Error::Unauthorized);
+        }
+        if comment.is_empty() {
+            return Err(Error::InvalidInput);
+        }
+
+        let entry = GoalComment {
+            author: author.clone(),
+            comment,
+            timestamp,
+        };
+        let index = append_goal_comment(&env, goal_id, &entry);
+
+        GoalCommentAdded {
+            goal_id,
+            index,
+            author,
+        }
+        .publish(&env);
+
+        Ok(index)
+    }
+
+    /// Get a goal's comment thread, oldest first.
+    pub fn get_goal_comments(env: Env, goal_id: u64) -> Vec<GoalComment> {
+        load_goal_comments(&env, goal_id)
+    }
+
+    /// Report a barrier on behalf of the plan's own patient. Fails unless
+    /// `patient_id` is the plan's patient.
+    pub fn report_patient_barrier(
+        env: Env,
+        care_plan_id: u64,
+        patient_id: Address,
+        barrier_type: Symbol,
+        description: String,
+        identified_date: u64,
+    ) -> Result<u64, Error> {
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if patient_id != plan.patient_id {
+            return Err(Error::Unauthorized);
+        }
+        Self::add_barrier(
+            env,
+            care_plan_id,
+            patient_id,
+            barrier_type,
+            description,
+            identified_date,
+        )
+    }
+
+    /// Add a barrier to a care plan. The reporter must be the plan's patient
+    /// or one of its providers; patient reports are tagged for triage.
+    pub fn add_barrier(
+        env: Env,
+        care_plan_id: u64,
+        reporter: Address,
+        barrier_type: Symbol,
+        description: String,
+        identified_date: u64,
+    ) -> Result<u64, Error> {
+        reporter.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        let reported_by_patient = reporter == plan.patient_id;
+        if reported_by_patient {
+            ensure_consent(&env, care_plan_id)?;
+        } else if !is_care_provider(&env, &plan, &reporter) {
+            return Err(Error::Unauthorized);
+        }
+
+        let barrier_id = next_barrier_id(&env);
+
+        let barrier = Barrier {
+            barrier_id,
+            care_plan_id,
+            reporter: reporter.clone(),
+            barrier_type,
+            description,
+            identified_date,
+            resolved: false,
+            resolution: None,
+            resolution_date: None,
+            resolved_by: None,
+            assigned_to: None,
+            reported_by_patient,
+        };
+
+        save_barrier(&env, &barrier);
+        add_plan_barrier(&env, care_plan_id, barrier_id);
+        update_barrier_stats(&env, care_plan_id, &barrier.barrier_type, false);
+
+        env.events().publish(
+            (Symbol::new(&env, "barrier_added"),),
+            (care_plan_id, barrier_id),
+        );
+
+        Ok(barrier_id)
+    }
+
+    /// Resolve a barrier.
+    pub fn resolve_barrier(
+        env: Env,
+        barrier_id: u64,
+        provider_id: Address,
+        resolution: String,
+        resolution_date: u64,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let barrier = load_barrier(&env, barrier_id).ok_or(Error::BarrierNotFound)?;
+        let plan = load_care_plan(&env, barrier.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+
+        if barrier.resolved {
+            return Err(Error::BarrierAlreadyResolved);
+        }
+
+        close_barrier(&env, barrier, &provider_id, resolution, resolution_date);
+        Ok(())
+    }
+
+    /// Resolve several barriers with the same resolution in one call.
+    /// Barriers that are already resolved are skipped; returns how many were
+    /// resolved. The whole batch is rejected if the provider is not on the
+    /// care team of any barrier's plan.
+    pub fn resolve_barriers_batch(
+        env: Env,
+        barrier_ids: Vec<u64>,
+        provider_id: Address,
+        resolution: String,
+        resolution_date: u64,
+    ) -> Result<u32, Error> {
+        provider_id.require_auth();
+
+        if barrier_ids.len() > MAX_BATCH {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut resolved = 0u32;
+        for barrier_id in barrier_ids.iter() {
+            let barrier = load_barrier(&env, barrier_id).ok_or(Error::BarrierNotFound)?;
+            let plan = load_care_plan(&env, barrier.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+            if !is_care_provider(&env, &plan, &provider_id) {
+                return Err(Error::Unauthorized);
+            }
+            if barrier.resolved {
+                continue;
+            }
+            close_barrier(
+                &env,
+                barrier,
+                &provider_id,
+                resolution.clone(),
+                resolution_date,
+            );
+            resolved += 1;
+        }
+        Ok(resolved)
+    }
+
+    /// Task a care team member with resolving an open barrier.
+    pub fn assign_barrier(
+        env: Env,
+        barrier_id: u64,
+        coordinator: Address,
+        assignee: Address,
+    ) -> Result<(), Error> {
+        coordinator.require_auth();
+
+        let mut barrier = load_barrier(&env, barrier_id).ok_or(Error::BarrierNotFound)?;
+        let plan = load_care_plan(&env, barrier.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &coordinator) {
+            return Err(Error::Unauthorized);
+        }
+        if barrier.resolved {
+            return Err(Error::BarrierAlreadyResolved);
+        }
+        let team = load_care_team(&env, plan.care_plan_id);
+        if !team.iter().any(|m| m.team_member == assignee) {
+            return Err(Error::InvalidInput);
+        }
+
+        barrier.assigned_to = Some(assignee.clone());
+        save_barrier(&env, &barrier);
+
+        BarrierAssigned {
+            barrier_id,
+            assignee,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// List a plan's barriers assigned to a specific care team member.
+    pub fn list_barriers_for_assignee(
+        env: Env,
+        care_plan_id: u64,
+        assignee: Address,
+    ) -> Vec<Barrier> {
+        let mut assigned = Vec::new(&env);
+        for id in load_plan_barrier_ids(&env, care_plan_id).iter() {
+            if let Some(b) = load_barrier(&env, id) {
+                if b.assigned_to.as_ref() == Some(&assignee) {
+                    assigned.push_back(b);
+                }
+            }
+        }
+        assigned
+    }
+
+    /// List unresolved barriers across all of a patient's live plans. The
+    /// patient sees every plan; anyone else only consented plans they provide
+    /// care on.
+    pub fn list_patient_open_barriers(
+        env: Env,
+        patient_id: Address,
+        requester: Address,
+    ) -> Vec<Barrier> {
+        requester.require_auth();
+
+        let mut open = Vec::new(&env);
+        for plan_id in load_patient_plans(&env, &patient_id).iter() {
+            let plan = match load_care_plan(&env, plan_id) {
+                Some(plan) => plan,
+                None => continue,
+            };
+            if requester != patient_id
+                && (!is_care_provider(&env, &plan, &requester)
+                    || ensure_consent(&env, plan_id).is_err())
+            {
+                continue;
+            }
+            for id in load_plan_barrier_ids(&env, plan_id).iter() {
+                if let Some(b) = load_barrier(&env, id) {
+                    if !b.resolved {
+                        open.push_back(b);
+                    }
+                }
+            }
+        }
+        open
+    }
+
+    /// Get open and resolved barrier counts for a plan, keyed by barrier type.
+    pub fn barrier_stats(env: Env, care_plan_id: u64) -> Map<Symbol, (u32, u32)> {
+        load_barrier_stats(&env, care_plan_id)
+    }
+
+    /// Record the patient's documented consent to a care plan.
+    pub fn record_patient_consent(
+        env: Env,
+        care_plan_id: u64,
+        patient_id: Address,
+        consent_hash: BytesN<32>,
+        consent_date: u64,
+    ) -> Result<(), Error> {
+        patient_id.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if plan.patient_id != patient_id {
+            return Err(Error::Unauthorized);
+        }
+
+        let consent = PlanConsent {
+            care_plan_id,
+            patient_id: patient_id.clone(),
+            consent_hash,
+            consent_date,
+            recorded_at: env.ledger().timestamp(),
+            revoked_at: None,
+        };
+
+        save_plan_consent(&env, &consent);
+
+        ConsentRecorded {
+            care_plan_id,
+            patient_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Revoke a previously recorded consent.
+    pub fn revoke_consent(env: Env, care_plan_id: u64, patient_id: Address) -> Result<(), Error> {
+        patient_id.require_auth();
+
+        let mut consent = load_plan_consent(&env, care_plan_id).ok_or(Error::ConsentRequired)?;
+        if consent.patient_id != patient_id {
+            return Err(Error::Unauthorized);
+        }
+
+        consent.revoked_at = Some(env.ledger().timestamp());
+        save_plan_consent(&env, &consent);
+
+        ConsentRevoked {
+            care_plan_id,
+            patient_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the consent record for a care plan, if any.
+    pub fn get_plan_consent(env: Env, care_plan_id: u64) -> Option<PlanConsent> {
+        load_plan_consent(&env, care_plan_id)
+    }
+
+    /// Schedule a review for a care plan.
+    pub fn schedule_care_plan_review(
+        env: Env,
+        care_plan_id: u64,
+        provider_id: Address,
+        review_date: u64,
+        review_type: Symbol,
+        review_kind: ReviewKind,
+    ) -> Result<u64, Error> {
+        provider_id.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+
+        let review_id = next_review_id(&env);
+
+        let review = CareReview {
+            review_id,
+            care_plan_id,
+            scheduled_by: provider_id.clone(),
+            review_date,
+            review_type,
+            review_kind,
+            conducted: false,
+            review_notes_hash: None,
+            plan_modifications: Vec::new(&env),
+            continue_plan: true,
+            conducted_by: None,
+            conducted_at: None,
+            cancelled: false,
+            cancel_reason: None,
+        };
+
+        save_review(&env, &review);
+        add_plan_review(&env, care_plan_id, review_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "review_scheduled"),),
+            (care_plan_id, review_id, review_date),
+        );
+
+        Ok(review_id)
+    }
+
+    /// Move an unconducted review to a new date that is not in the past.
+    pub fn reschedule_review(
+        env: Env,
+        review_id: u64,
+        provider_id: Address,
+        new_date: u64,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut review = load_review(&env, review_id).ok_or(Error::ReviewNotFound)?;
+        let plan = load_care_plan(&env, review.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if review.conducted {
+            return Err(Error::ReviewAlreadyConducted);
+        }
+        if review.cancelled {
+            return Err(Error::ReviewCancelled);
+        }
+        if new_date < env.ledger().timestamp() {
+            return Err(Error::InvalidInput);
+        }
+
+        let previous_date = review.review_date;
+        review.review_date = new_date;
+        save_review(&env, &review);
+
+        ReviewRescheduled {
+            review_id,
+            previous_date,
+            new_date,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Cancel an unconducted review, keeping its record.
+    pub fn cancel_review(
+        env: Env,
+        review_id: u64,
+        provider_id: Address,
+        reason: String,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let mut review = load_review(&env, review_id).ok_or(Error::ReviewNotFound)?;
+        let plan = load_care_plan(&env, review.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+        if review.conducted {
+            return Err(Error::ReviewAlreadyConducted);
+        }
+        if review.cancelled {
+            return Err(Error::ReviewCancelled);
+        }
+
+        review.cancelled = true;
+        review.cancel_reason = Some(reason);
+        save_review(&env, &review);
+
+        ReviewCancelled {
+            review_id,
+            provider_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// List ids of a plan's reviews whose date has passed without being
+    /// conducted or cancelled.
+    pub fn list_overdue_reviews(env: Env, care_plan_id: u64) -> Vec<u64> {
+        let now = env.ledger().timestamp();
+        let mut overdue = Vec::new(&env);
+        for id in load_plan_reviews(&env, care_plan_id).iter() {
+            if let Some(r) = load_review(&env, id) {
+                if !r.conducted && !r.cancelled && r.review_date < now {
+                    overdue.push_back(id);
+                }
+            }
+        }
+        overdue
+    }
+
+    /// Get a care plan review.
+    pub fn get_review(env: Env, review_id: u64) -> Result<CareReview, Error> {
+        load_review(&env, review_id).ok_or(Error::ReviewNotFound)
+    }
+
+    /// List a plan's reviews in scheduling order, `limit` at a time from
+    /// `start`. A limit of zero returns every remaining review.
+    pub fn list_plan_reviews(
+        env: Env,
+        care_plan_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<CareReview> {
+        let ids = load_plan_reviews(&env, care_plan_id);
+        let mut page = Vec::new(&env);
+        page_ids(&ids, start, limit, |id| match load_review(&env, id) {
+            Some(review) => {
+                page.push_back(review);
+                true
+            }
+            None => false,
+        });
+        page
+    }
+
+    /// Conduct a previously scheduled care plan review. Each plan
+    /// modification must be non-empty, and at most MAX_PLAN_ENTRIES may be
+    /// given. When the plan continues and `schedule_next` is set, the
+    /// follow-up review is created at the plan's next review date and its id
+    /// returned.
+    pub fn conduct_care_plan_review(
+        env: Env,
+        review_id: u64,
+        provider_id: Address,
+        review_notes_hash: BytesN<32>,
+        plan_modifications: Vec<String>,
+        continue_plan: bool,
+        schedule_next: bool,
+    ) -> Result<Option<u64>, Error> {
+        provider_id.require_auth();
+
+        let mut review = load_review(&env, review_id).ok_or(Error::ReviewNotFound)?;
+
+        let mut plan = load_care_plan(&env, review.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+
+        if review.conducted {
+            return Err(Error::ReviewAlreadyConducted);
+        }
+        if review.cancelled {
+            return Err(Error::ReviewCancelled);
+        }
+        if plan_modifications.len() > MAX_PLAN_ENTRIES
+            || plan_modifications.iter().any(|m| m.is_empty())
+        {
+            return Err(Error::InvalidInput);
+        }
+
+        let conducted_at = env.ledger().timestamp();
+
+        review.conducted = true;
+        review.review_notes_hash = Some(review_notes_hash);
+        review.plan_modifications = plan_modifications;
+        review.continue_plan = continue_plan;
+        review.conducted_by = Some(provider_id.clone());
+        review.conducted_at = Some(conducted_at);
+
+        let mut follow_up_id = None;
+
+        // Update the parent care plan's last/next review dates
+        plan.last_review_date = Some(conducted_at);
+        if matches!(review.review_kind, ReviewKind::Scheduled) {
+            plan.next_review_date = checked_add_days(conducted_at, plan.review_frequency_days)?;
+        }
+
+        if !continue_plan {
+            plan.status = CarePlanStatus::Completed;
+            append_status_history(&env, plan.care_plan_id, &plan.status);
+        } else if matches!(plan.status, CarePlanStatus::UnderReview) {
+            plan.status = CarePlanStatus::Active;
+            append_status_history(&env, plan.care_plan_id, &plan.status);
+        }
+
+        save_care_plan(&env, &plan);
+
+        if continue_plan && schedule_next {
+            let id = next_review_id(&env);
+            let follow_up = CareReview {
+                review_id: id,
+                care_plan_id: plan.care_plan_id,
+                scheduled_by: provider_id.clone(),
+                review_date: plan.next_review_date,
+                review_type: review.review_type.clone(),
+                review_kind: ReviewKind::Scheduled,
+                conducted: false,
+                review_notes_hash: None,
+                plan_modifications: Vec::new(&env),
+                continue_plan: true,
+                conducted_by: None,
+                conducted_at: None,
+                cancelled: false,
+                cancel_reason: None,
+            };
+            save_review(&env, &follow_up);
+            add_plan_review(&env, plan.care_plan_id, id);
+            follow_up_id = Some(id);
+        }
+
+        save_review(&env, &review);
+
+        env.events().publish(
+            (Symbol::new(&env, "review_conducted"),),
+            (review_id, provider_id, continue_plan),
+        );
+
+        Ok(follow_up_id)
+    }
+
+    /// Assign a care team member to a care plan. Only the plan's
+    /// coordinating provider, or a member whose role is granted manage_team,
+    /// may change the team.
+    pub fn assign_care_team_member(
+        env: Env,
+        care_plan_id: u64,
+        coordinating_provider: Address,
+        team_member: Address,
+        role: Symbol,
+        responsibilities: Vec<String>,
+    ) -> Result<(), Error> {
+        coordinating_provider.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if plan.provider_id != coordinating_provider {
+            // Delegated coordinators need an explicit manage_team grant and
+            // cannot hand that grant on.
+            if !role_explicitly_permits(&env, &plan, &coordinating_provider, "manage_team")
+                || role_has_permission(&env, care_plan_id, &role, "manage_team")
+            {
+                return Err(Error::Unauthorized);
+            }
+        }
+
+        let mut team = load_care_team(&env, care_plan_id);
+
+        save_team_member_role(&env, care_plan_id, &team_member, &role);
+        let member = CareTeamMember {
+            care_plan_id,
+            team_member: team_member.clone(),
+            role,
+            responsibilities,
+            assigned_by: coordinating_provider.clone(),
+            assigned_at: env.ledger().timestamp(),
+        };
+
+        team.push_back(member);
+        save_care_team(&env, care_plan_id, &team);
+
+        env.events().publish(
+            (Symbol::new(&env, "team_member_assigned"),),
+            (care_plan_id, team_member),
+        );
+
+        Ok(())
+    }
+
+    /// Role of a care team member on a plan, from their most recent
+    /// assignment. None when the address is not on the team.
+    pub fn get_team_member_role(
+        env: Env,
+        care_plan_id: u64,
+        team_member: Address,
+    ) -> Option<Symbol> {
+        load_team_member_role(&env, care_plan_id, &team_member)
+    }
+
+    /// Set the permissions granted to a care team role on this plan, e.g.
+    /// add_intervention or achieve_goal. Only the plan's coordinating
+    /// provider may change the matrix; an empty list revokes everything.
+    pub fn set_role_permissions(
+        env: Env,
+        care_plan_id: u64,
+        coordinating_provider: Address,
+        role: Symbol,
+        permissions: Vec<Symbol>,
+    ) -> Result<(), Error> {
+        coordinating_provider.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if plan.provider_id != coordinating_provider {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut matrix = load_role_permissions(&env, care_plan_id);
+        matrix.set(role.clone(), permissions);
+        save_role_permissions(&env, care_plan_id, &matrix);
+
+        RolePermissionsSet { care_plan_id, role }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Role -> permissions matrix configured for a plan.
+    pub fn get_role_permissions(env: Env, care_plan_id: u64) -> Map<Symbol, Vec<Symbol>> {
+        load_role_permissions(&env, care_plan_id)
+    }
+
+    /// Tally the outcomes of a completed or discontinued plan. The report is
+    /// stored on first generation and returned unchanged afterwards.
+    pub fn generate_completion_report(
+        env: Env,
+        care_plan_id: u64,
+    ) -> Result<CompletionReport, Error> {
+        if let Some(report) = load_completion_report(&env, care_plan_id) {
+            return Ok(report);
+        }
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !matches!(
+            plan.status,
+            CarePlanStatus::Completed | CarePlanStatus::Discontinued
+        ) {
+            return Err(Error::PlanNotCompleted);
+        }
+
+        let mut goals_achieved = 0u32;
+        let mut goals_discontinued = 0u32;
+        let mut goals_open = 0u32;
+        for id in load_plan_goals(&env, care_plan_id).iter() {
+            if let Some(g) = load_goal(&env, id) {
+                if g.voided {
+                    continue;
+                }
+                match g.status {
+                    GoalStatus::Achieved => goals_achieved += 1,
+                    GoalStatus::Discontinued => goals_discontinued += 1,
+                    _ => goals_open += 1,
+                }
+            }
+        }
+
+        let mut barriers_resolved = 0u32;
+        let mut barriers_open = 0u32;
+        for id in load_plan_barrier_ids(&env, care_plan_id).iter() {
+            if let Some(b) = load_barrier(&env, id) {
+                if b.resolved {
+                    barriers_resolved += 1;
+                } else {
+                    barriers_open += 1;
+                }
+            }
+        }
+
+        let mut reviews_conducted = 0u32;
+        for id in load_plan_reviews(&env, care_plan_id).iter() {
+            if let Some(r) = load_review(&env, id) {
+                if r.conducted {
+                    reviews_conducted += 1;
+                }
             }
         }
 
+        let report = CompletionReport {
+            care_plan_id,
+            final_status: plan.status,
+            goals_achieved,
+            goals_discontinued,
+            goals_open,
+            barriers_resolved,
+            barriers_open,
+            reviews_conducted,
+            generated_at: env.ledger().timestamp(),
+        };
+        save_completion_report(&env, &report);
+
+        CompletionReportGenerated { care_plan_id }.publish(&env);
+
+        Ok(report)
+    }
+
+    /// Record the emergency contact for a care plan, replacing any previous one.
+    pub fn set_emergency_contact(
+        env: Env,
+        care_plan_id: u64,
+        provider_id: Address,
+        contact: Address,
+        relationship: Symbol,
+        reachable_via_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &provider_id) {
+            return Err(Error::Unauthorized);
+        }
+
+        let record = EmergencyContact {
+            care_plan_id,
+            contact: contact.clone(),
+            relationship,
+            reachable_via_hash,
+            set_by: provider_id,
+            set_at: env.ledger().timestamp(),
+        };
+        save_emergency_contact(&env, &record);
+
+        EmergencyContactSet {
+            care_plan_id,
+            contact,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the emergency contact recorded for a care plan, if any.
+    pub fn get_emergency_contact(env: Env, care_plan_id: u64) -> Option<EmergencyContact> {
+        load_emergency_contact(&env, care_plan_id)
+    }
+
+    /// Append a note to the plan's log and return its index. Only the hash of
+    /// the note is stored.
+    pub fn add_plan_note(
+        env: Env,
+        care_plan_id: u64,
+        author: Address,
+        note_hash: BytesN<32>,
+        timestamp: u64,
+    ) -> Result<u32, Error> {
+        author.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !is_care_provider(&env, &plan, &author) {
+            return Err(Error::Unauthorized);
+        }
+
+        let note = PlanNote {
+            author: author.clone(),
+            note_hash,
+            timestamp,
+        };
+        let index = append_plan_note(&env, care_plan_id, &note);
+
+        PlanNoteAdded {
+            care_plan_id,
+            index,
+            author,
+        }
+        .publish(&env);
+
+        Ok(index)
+    }
+
+    /// Notes on a plan in the order they were added.
+    pub fn get_plan_notes(env: Env, care_plan_id: u64) -> Vec<PlanNote> {
+        load_plan_notes(&env, care_plan_id)
+    }
+
+    /// Archive a completed or discontinued plan into a compact record and
+    /// remove its detailed per-entity storage.
+    pub fn archive_care_plan(env: Env, care_plan_id: u64, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if !matches!(
+            plan.status,
+            CarePlanStatus::Completed | CarePlanStatus::Discontinued
+        ) {
+            return Err(Error::PlanNotArchivable);
+        }
+
+        archive_plan(&env, plan, admin);
+        Ok(())
+    }
+
+    /// Set how many days a Completed plan keeps its detailed records before
+    /// `auto_archive_eligible` may archive it.
+    pub fn set_archive_policy(
+        env: Env,
+        admin: Address,
+        completed_retention_days: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        set_archive_retention_days(&env, completed_retention_days);
+        Ok(())
+    }
+
+    /// Archive a Completed plan once it has been completed for longer than
+    /// the archive policy allows. Anyone may call this as a keeper; returns
+    /// whether the plan was archived. Without a policy nothing is archived.
+    pub fn auto_archive_eligible(env: Env, care_plan_id: u64) -> Result<bool, Error> {
+        if load_archive(&env, care_plan_id).is_some() {
+            return Ok(false);
+        }
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        let Some(days) = get_archive_retention_days(&env) else {
+            return Ok(false);
+        };
+        if !matches!(plan.status, CarePlanStatus::Completed) {
+            return Ok(false);
+        }
+
+        let completed_at = load_status_history(&env, care_plan_id)
+            .iter()
+            .filter(|(status, _)| matches!(status, CarePlanStatus::Completed))
+            .map(|(_, at)| at)
+            .last()
+            .unwrap_or(plan.created_at);
+        let archive_after = checked_add_days(completed_at, days)?;
+        if env.ledger().timestamp() <= archive_after {
+            return Ok(false);
+        }
+
+        archive_plan(&env, plan, env.current_contract_address());
+        Ok(true)
+    }
+
+    /// Get the archived record of a care plan, if it has been archived.
+    pub fn get_archived_plan(env: Env, care_plan_id: u64) -> Option<ArchivedPlan> {
+        load_archive(&env, care_plan_id)
+    }
+
+    /// Aggregate plan, goal and barrier counts across a patient's plans. The
+    /// patient sees every plan; anyone else only plans they provide care on
+    /// and, when consent is required, the patient has consented to.
+    pub fn get_patient_overview(
+        env: Env,
+        patient_id: Address,
+        requester: Address,
+    ) -> Result<PatientOverview, Error> {
+        requester.require_auth();
+
+        let mut total_plans = 0u32;
+        let mut active_plans = 0u32;
+        let mut active_goals = 0u32;
+        let mut open_barriers = 0u32;
+
+        for plan_id in load_patient_plans(&env, &patient_id).iter() {
+            let plan = match load_care_plan(&env, plan_id) {
+                Some(plan) => plan,
+                None => continue,
+            };
+            if requester != patient_id
+                && (!is_care_provider(&env, &plan, &requester)
+                    || ensure_consent(&env, plan_id).is_err())
+            {
+                continue;
+            }
+            total_plans += 1;
+            if matches!(
+                plan.status,
+                CarePlanStatus::Active | CarePlanStatus::UnderReview
+            ) {
+                active_plans += 1;
+            }
+            for goal_id in load_plan_goals(&env, plan_id).iter() {
+                if let Some(g) = load_goal(&env, goal_id) {
+                    if goal_is_open(&g) {
+                        active_goals += 1;
+                    }
+                }
+            }
+            for (_, (open, _)) in load_barrier_stats(&env, plan_id).iter() {
+                open_barriers += open;
+            }
+        }
+
+        Ok(PatientOverview {
+            patient_id,
+            total_plans,
+            active_plans,
+            active_goals,
+            open_barriers,
+        })
+    }
+
+    /// Get a summary of a care plan. Each list is paged from its offset; a
+    /// limit of zero returns every remaining entry.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_care_plan_summary(
+        env: Env,
+        care_plan_id: u64,
+        requester: Address,
+        goal_offset: u32,
+        goal_limit: u32,
+        intervention_offset: u32,
+        intervention_limit: u32,
+        barrier_offset: u32,
+        barrier_limit: u32,
+    ) -> Result<CarePlanSummary, Error> {
+        requester.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+
+        // Collect active goals
+        let goal_ids = load_plan_goals(&env, care_plan_id);
+        let mut active_goals: Vec<CareGoal> = Vec::new(&env);
+        let next_goal_offset = page_ids(&goal_ids, goal_offset, goal_limit, |id| {
+            if let Some(g) = load_goal(&env, id) {
+                if goal_is_open(&g) {
+                    active_goals.push_back(g);
+                    return true;
+                }
+            }
+            false
+        });
+
+        // Collect interventions
+        let intervention_ids = load_plan_interventions(&env, care_plan_id);
+        let mut interventions: Vec<Intervention> = Vec::new(&env);
+        let next_intervention_offset =
+            page_ids(&intervention_ids, intervention_offset, intervention_limit, |id| {
+                match load_intervention(&env, id) {
+                    Some(i) => {
+                        interventions.push_back(i);
+                        true
+                    }
+                    None => false,
+                }
+            });
+
+        // Collect barriers
+        let barrier_ids = load_plan_barrier_ids(&env, care_plan_id);
+        let mut barriers: Vec<Barrier> = Vec::new(&env);
+        let next_barrier_offset = page_ids(&barrier_ids, barrier_offset, barrier_limit, |id| {
+            match load_barrier(&env, id) {
+                Some(b) => {
+                    barriers.push_back(b);
+                    true
+                }
+                None => false,
+            }
+        });
+
         let care_team = load_care_team(&env, care_plan_id);
-        let barriers = load_plan_barriers(&env, care_plan_id);
 
         Ok(CarePlanSummary {
             care_plan_id,
@@ -455,6 +2476,895 @@ impl CarePlanContract {
             barriers,
             last_review_date: plan.last_review_date,
             next_review_date: plan.next_review_date,
+            next_goal_offset,
+            next_intervention_offset,
+            next_barrier_offset,
+        })
+    }
+
+    /// Bundle a plan with all of its goals (voided ones included),
+    /// interventions, barriers, reviews and care team. Only the patient and
+    /// the plan's providers may export it.
+    pub fn export_snapshot(
+        env: Env,
+        care_plan_id: u64,
+        requester: Address,
+    ) -> Result<PlanSnapshot, Error> {
+        requester.require_auth();
+
+        let plan = load_care_plan(&env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+        if requester != plan.patient_id && !is_care_provider(&env, &plan, &requester) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut goals = Vec::new(&env);
+        let mut progress = Vec::new(&env);
+        let mut outcomes = Vec::new(&env);
+        for id in load_plan_goals(&env, care_plan_id).iter() {
+            if let Some(g) = load_goal(&env, id) {
+                progress.append(&load_goal_progress(&env, &g));
+                goals.push_back(g);
+            }
+            let outcome = load_goal_outcome(&env, id);
+            let achieved_at = load_goal_achieved_at(&env, id);
+            if outcome.is_some() || achieved_at.is_some() {
+                outcomes.push_back(GoalOutcomeRecord {
+                    goal_id: id,
+                    achievement_date: outcome.as_ref().map(|(date, _)| *date),
+                    outcome_notes: outcome.map(|(_, notes)| notes),
+                    achieved_at,
+                });
+            }
+        }
+        let mut interventions = Vec::new(&env);
+        for id in load_plan_interventions(&env, care_plan_id).iter() {
+            if let Some(i) = load_intervention(&env, id) {
+                interventions.push_back(i);
+            }
+        }
+        let mut barriers = Vec::new(&env);
+        for id in load_plan_barrier_ids(&env, care_plan_id).iter() {
+            if let Some(b) = load_barrier(&env, id) {
+                barriers.push_back(b);
+            }
+        }
+        let mut reviews = Vec::new(&env);
+        for id in load_plan_reviews(&env, care_plan_id).iter() {
+            if let Some(r) = load_review(&env, id) {
+                reviews.push_back(r);
+            }
+        }
+
+        Ok(PlanSnapshot {
+            plan,
+            goals,
+            progress,
+            interventions,
+            barriers,
+            reviews,
+            care_team: load_care_team(&env, care_plan_id),
+            outcomes,
+            exported_at: env.ledger().timestamp(),
         })
     }
-}
\ No newline at end of file
+
+    /// Re-create an exported plan and its children under fresh ids, keeping
+    /// goal dependencies, goal outcomes and every child's link to the plan. Returns the new
+    /// care plan id.
+    pub fn import_snapshot(env: Env, admin: Address, snapshot: PlanSnapshot) -> Result<u64, Error> {
+        require_admin(&env, &admin)?;
+
+        let PlanSnapshot {
+            mut plan,
+            goals,
+            progress,
+            interventions,
+            barriers,
+            reviews,
+            care_team,
+            outcomes,
+            ..
+        } = snapshot;
+        let source_id = plan.care_plan_id;
+        let belongs = goals.iter().all(|g| g.care_plan_id == source_id)
+            && progress
+                .iter()
+                .all(|p| goals.iter().any(|g| g.goal_id == p.goal_id))
+            && outcomes
+                .iter()
+                .all(|o| goals.iter().any(|g| g.goal_id == o.goal_id))
+            && interventions.iter().all(|i| i.care_plan_id == source_id)
+            && barriers.iter().all(|b| b.care_plan_id == source_id)
+            && reviews.iter().all(|r| r.care_plan_id == source_id);
+        if !belongs {
+            return Err(Error::InvalidInput);
+        }
+
+        let open = matches!(
+            plan.status,
+            CarePlanStatus::Active | CarePlanStatus::UnderReview | CarePlanStatus::PendingApproval
+        );
+        if open {
+            ensure_single_active_slot(&env, &plan.patient_id, &plan.plan_type, None)?;
+        }
+
+        let care_plan_id = next_care_plan_id(&env);
+        plan.care_plan_id = care_plan_id;
+        save_care_plan(&env, &plan);
+        append_status_history(&env, care_plan_id, &plan.status);
+        add_patient_plan(&env, &plan.patient_id, care_plan_id);
+        add_provider_plan(&env, &plan.provider_id, care_plan_id);
+        for condition in plan.conditions.iter() {
+            add_condition_plan(&env, &condition, care_plan_id);
+        }
+        for condition in plan.coded_conditions.iter() {
+            add_condition_code_plan(&env, &condition.code, care_plan_id);
+        }
+
+        // Allocate every goal id first so dependencies can be remapped
+        let mut goal_ids: Map<u64, u64> = Map::new(&env);
+        for goal in goals.iter() {
+            goal_ids.set(goal.goal_id, next_goal_id(&env));
+        }
+        // Entries keep their order per goal, so correction indexes stay valid
+        let mut progress_counts: Map<u64, u32> = Map::new(&env);
+        for mut entry in progress.iter() {
+            entry.goal_id = goal_ids.get(entry.goal_id).unwrap();
+            let index = progress_counts.get(entry.goal_id).unwrap_or(0);
+            save_progress_entry(&env, entry.goal_id, index, &entry);
+            progress_counts.set(entry.goal_id, index + 1);
+        }
+        for mut goal in goals.iter() {
+            goal.goal_id = goal_ids.get(goal.goal_id).unwrap();
+            goal.care_plan_id = care_plan_id;
+            goal.progress_count = progress_counts.get(goal.goal_id).unwrap_or(0);
+            let mut depends_on = Vec::new(&env);
+            for old in goal.depends_on.iter() {
+                if let Some(new) = goal_ids.get(old) {
+                    depends_on.push_back(new);
+                }
+            }
+            goal.depends_on = depends_on;
+
+            save_goal(&env, &goal);
+            add_plan_goal(&env, care_plan_id, goal.goal_id);
+            if let Some(metric) = &goal.vital_metric {
+                add_vital_goal(&env, &plan.patient_id, metric, goal.goal_id);
+            }
+            if !goal.voided {
+                let achieved = matches!(goal.status, GoalStatus::Achieved);
+                update_provider_goal_stats(&env, &goal.created_by, achieved as i32, 1);
+            }
+        }
+
+        for outcome in outcomes.iter() {
+            let goal_id = goal_ids.get(outcome.goal_id).unwrap();
+            if let (Some(date), Some(notes)) = (outcome.achievement_date, outcome.outcome_notes) {
+                save_goal_outcome(&env, goal_id, date, &notes);
+            }
+            if let Some(achieved_at) = outcome.achieved_at {
+                save_goal_achieved_at(&env, goal_id, achieved_at);
+            }
+        }
+
+        for mut intervention in interventions.iter() {
+            intervention.intervention_id = next_intervention_id(&env);
+            intervention.care_plan_id = care_plan_id;
+            intervention.goal_id = intervention.goal_id.and_then(|old| goal_ids.get(old));
+            save_intervention(&env, &intervention);
+            add_plan_intervention(&env, care_plan_id, intervention.intervention_id);
+        }
+
+        for mut barrier in barriers.iter() {
+            barrier.barrier_id = next_barrier_id(&env);
+            barrier.care_plan_id = care_plan_id;
+            save_barrier(&env, &barrier);
+            add_plan_barrier(&env, care_plan_id, barrier.barrier_id);
+            update_barrier_stats(&env, care_plan_id, &barrier.barrier_type, false);
+            if barrier.resolved {
+                update_barrier_stats(&env, care_plan_id, &barrier.barrier_type, true);
+            }
+        }
+
+        for mut review in reviews.iter() {
+            review.review_id = next_review_id(&env);
+            review.care_plan_id = care_plan_id;
+            save_review(&env, &review);
+            add_plan_review(&env, care_plan_id, review.review_id);
+        }
+
+        let mut team = Vec::new(&env);
+        for mut member in care_team.iter() {
+            member.care_plan_id = care_plan_id;
+            save_team_member_role(&env, care_plan_id, &member.team_member, &member.role);
+            team.push_back(member);
+        }
+        save_care_team(&env, care_plan_id, &team);
+
+        CarePlanImported {
+            care_plan_id,
+            source_id,
+            admin,
+        }
+        .publish(&env);
+
+        Ok(care_plan_id)
+    }
+}
+
+/// Validate and store a new care plan under `care_plan_id`, or the next
+/// counter id when None, returning the id used.
+#[allow(clippy::too_many_arguments)]
+fn open_care_plan(
+    env: &Env,
+    care_plan_id: Option<u64>,
+    patient_id: Address,
+    provider_id: Address,
+    plan_type: Symbol,
+    conditions: Vec<String>,
+    goals: Vec<String>,
+    start_date: u64,
+    review_frequency_days: u32,
+    end_date: Option<u64>,
+) -> Result<u64, Error> {
+    if conditions.is_empty()
+        || conditions.len() > MAX_PLAN_ENTRIES
+        || goals.len() > MAX_PLAN_ENTRIES
+        || end_date.is_some_and(|end| end <= start_date)
+    {
+        return Err(Error::InvalidInput);
+    }
+
+    ensure_single_active_slot(env, &patient_id, &plan_type, None)?;
+
+    let care_plan_id = care_plan_id.unwrap_or_else(|| next_care_plan_id(env));
+    let next_review_date = checked_add_days(start_date, review_frequency_days)?;
+    let status = if is_cosign_required(env) {
+        CarePlanStatus::PendingApproval
+    } else {
+        CarePlanStatus::Active
+    };
+
+    for condition in conditions.iter() {
+        add_condition_plan(env, &condition, care_plan_id);
+    }
+
+    let plan = CarePlan {
+        care_plan_id,
+        patient_id: patient_id.clone(),
+        provider_id: provider_id.clone(),
+        plan_type,
+        conditions,
+        coded_conditions: Vec::new(env),
+        goals,
+        start_date,
+        review_frequency_days,
+        status,
+        next_review_date,
+        last_review_date: None,
+        end_date,
+        created_at: env.ledger().timestamp(),
+    };
+
+    save_care_plan(env, &plan);
+    append_status_history(env, care_plan_id, &plan.status);
+    add_patient_plan(env, &patient_id, care_plan_id);
+    add_provider_plan(env, &provider_id, care_plan_id);
+
+    env.events().publish(
+        (Symbol::new(env, "care_plan_created"),),
+        (care_plan_id, patient_id, provider_id),
+    );
+
+    Ok(care_plan_id)
+}
+
+/// Plan id derived from sha256(patient || provider || external_ref), with the
+/// top bit set to keep it apart from counter-assigned ids.
+fn derive_plan_id(
+    env: &Env,
+    patient_id: &Address,
+    provider_id: &Address,
+    external_ref: &BytesN<32>,
+) -> u64 {
+    let mut data = patient_id.clone().to_xdr(env);
+    data.append(&provider_id.clone().to_xdr(env));
+    data.append(&Bytes::from(external_ref));
+    let hash = env.crypto().sha256(&data).to_array();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(head) | (1 << 63)
+}
+
+/// Compute `start + days` in seconds, rejecting overflow.
+fn checked_add_days(start: u64, days: u32) -> Result<u64, Error> {
+    (days as u64)
+        .checked_mul(86_400)
+        .and_then(|secs| start.checked_add(secs))
+        .ok_or(Error::InvalidInput)
+}
+
+/// Walk `ids` from `offset`, calling `take` on each until `limit` entries
+/// are taken (zero means no limit). Returns the offset to resume from, or
+/// None when the list is exhausted.
+fn page_ids(
+    ids: &Vec<u64>,
+    offset: u32,
+    limit: u32,
+    mut take: impl FnMut(u64) -> bool,
+) -> Option<u32> {
+    let mut taken = 0u32;
+    let mut index = offset;
+    while index < ids.len() {
+        if limit > 0 && taken == limit {
+            return Some(index);
+        }
+        if take(ids.get(index).unwrap()) {
+            taken += 1;
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Verify `admin` is the stored contract administrator.
+fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+    admin.require_auth();
+    let stored = get_admin(env).ok_or(Error::NotInitialized)?;
+    if stored != *admin {
+        return Err(Error::Unauthorized);
+    }
+    Ok(())
+}
+
+/// When the single-active-plan rule is on, reject if the patient already has
+/// an open plan of this type other than `except`. Plans under review or
+/// awaiting co-signature hold the slot as well as Active ones, except that
+/// when activating `except` other waiting plans don't block it.
+fn ensure_single_active_slot(
+    env: &Env,
+    patient_id: &Address,
+    plan_type: &Symbol,
+    except: Option<u64>,
+) -> Result<(), Error> {
+    if !is_single_active_plan(env) {
+        return Ok(());
+    }
+    for id in load_patient_plans(env, patient_id).iter() {
+        if Some(id) == except {
+            continue;
+        }
+        if let Some(existing) = load_care_plan(env, id) {
+            let open = match existing.status {
+                CarePlanStatus::Active | CarePlanStatus::UnderReview => true,
+                CarePlanStatus::PendingApproval => except.is_none(),
+                CarePlanStatus::Completed | CarePlanStatus::Discontinued => false,
+            };
+            if open && existing.plan_type == *plan_type {
+                return Err(Error::DuplicateActivePlan);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `address` is the plan's provider or a member of its care team.
+fn is_care_provider(env: &Env, plan: &CarePlan, address: &Address) -> bool {
+    if plan.provider_id == *address {
+        return true;
+    }
+    load_care_team(env, plan.care_plan_id)
+        .iter()
+        .any(|member| member.team_member == *address)
+}
+
+/// Reject `address` unless one of its care team roles grants `permission`.
+/// The coordinating provider is always allowed, as is everyone on a plan
+/// with no role matrix configured.
+fn ensure_role_permits(
+    env: &Env,
+    plan: &CarePlan,
+    address: &Address,
+    permission: &str,
+) -> Result<(), Error> {
+    if plan.provider_id == *address {
+        return Ok(());
+    }
+    let matrix = load_role_permissions(env, plan.care_plan_id);
+    if matrix.is_empty() {
+        return Ok(());
+    }
+
+    if role_explicitly_permits(env, plan, address, permission) {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized)
+    }
+}
+
+/// Whether a care team member holds a role the plan's matrix grants the
+/// permission to. Unlike `ensure_role_permits`, an empty matrix grants
+/// nothing.
+fn role_explicitly_permits(
+    env: &Env,
+    plan: &CarePlan,
+    address: &Address,
+    permission: &str,
+) -> bool {
+    load_care_team(env, plan.care_plan_id)
+        .iter()
+        .filter(|member| member.team_member == *address)
+        .any(|member| role_has_permission(env, plan.care_plan_id, &member.role, permission))
+}
+
+/// Whether the plan's matrix grants the permission to a role.
+fn role_has_permission(env: &Env, care_plan_id: u64, role: &Symbol, permission: &str) -> bool {
+    load_role_permissions(env, care_plan_id)
+        .get(role.clone())
+        .is_some_and(|perms| perms.contains(Symbol::new(env, permission)))
+}
+
+/// Validate and append a progress entry to a goal on behalf of the patient
+/// or a care team member.
+fn append_progress(
+    env: &Env,
+    goal_id: u64,
+    recorded_by: Address,
+    current_value: String,
+    progress_note: String,
+    numeric_value: Option<i64>,
+    recorded_date: u64,
+) -> Result<(), Error> {
+    recorded_by.require_auth();
+
+    let mut goal = load_active_goal(env, goal_id)?;
+    let plan = load_care_plan(env, goal.care_plan_id).ok_or(Error::CarePlanNotFound)?;
+    if recorded_by != plan.patient_id && !is_care_provider(env, &plan, &recorded_by) {
+        return Err(Error::Unauthorized);
+    }
+
+    if matches!(goal.status, GoalStatus::Achieved) {
+        return Err(Error::GoalAlreadyAchieved);
+    }
+    if matches!(goal.status, GoalStatus::Discontinued) {
+        return Err(Error::GoalDiscontinued);
+    }
+    if numeric_value.is_some() && goal.target_number.is_none() {
+        return Err(Error::InvalidInput);
+    }
+    ensure_consent(env, goal.care_plan_id)?;
+    ensure_dependencies_met(env, &goal)?;
+
+    let entry = ProgressEntry {
+        goal_id,
+        patient_id: plan.patient_id,
+        recorded_by: recorded_by.clone(),
+        current_value,
+        progress_note,
+        numeric_value,
+        recorded_date,
+        superseded: false,
+        corrects: None,
+    };
+
+    push_progress_entry(env, &mut goal, &entry);
+    save_goal(env, &goal);
+
+    env.events().publish(
+        (Symbol::new(env, "goal_progress_recorded"),),
+        (goal_id, recorded_by),
+    );
+
+    Ok(())
+}
+
+/// Whether a raw stored struct has a field with this name. Layouts are told
+/// apart by a field only one of them has.
+fn has_field(env: &Env, raw: &Val, name: &str) -> Result<bool, Error> {
+    let fields = Map::<Symbol, Val>::try_from_val(env, raw).map_err(|_| Error::InvalidInput)?;
+    Ok(fields.contains_key(Symbol::new(env, name)))
+}
+
+/// Migrate a plan record and every record filed under it. Progress entries
+/// are covered by the goal migration, since the original layout kept them
+/// inside the goal.
+fn migrate_plan_records(env: &Env, care_plan_id: u64) -> Result<u32, Error> {
+    let mut migrated = 0u32;
+    if migrate_care_plan_record(env, care_plan_id)? {
+        migrated += 1;
+    }
+    for goal_id in load_plan_goals(env, care_plan_id).iter() {
+        match migrate_goal_record(env, goal_id) {
+            Ok(true) => migrated += 1,
+            Ok(false) | Err(Error::GoalNotFound) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    for intervention_id in load_plan_interventions(env, care_plan_id).iter() {
+        if migrate_intervention_record(env, intervention_id)? {
+            migrated += 1;
+        }
+    }
+    for barrier_id in load_plan_barrier_ids(env, care_plan_id).iter() {
+        if migrate_barrier_record(env, barrier_id)? {
+            migrated += 1;
+        }
+    }
+    for review_id in load_plan_reviews(env, care_plan_id).iter() {
+        if migrate_review_record(env, review_id)? {
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        // Legacy barriers were never counted, so recount from the records
+        let mut stats: Map<Symbol, (u32, u32)> = Map::new(env);
+        for barrier_id in load_plan_barrier_ids(env, care_plan_id).iter() {
+            if let Some(barrier) = load_barrier(env, barrier_id) {
+                let (open, closed) = stats.get(barrier.barrier_type.clone()).unwrap_or((0, 0));
+                let counts = if barrier.resolved {
+                    (open, closed + 1)
+                } else {
+                    (open + 1, closed)
+                };
+                stats.set(barrier.barrier_type, counts);
+            }
+        }
+        save_barrier_stats(env, care_plan_id, &stats);
+
+        PlanMigrated {
+            care_plan_id,
+            migrated,
+        }
+        .publish(env);
+    }
+    Ok(migrated)
+}
+
+fn migrate_care_plan_record(env: &Env, care_plan_id: u64) -> Result<bool, Error> {
+    let raw = load_care_plan_raw(env, care_plan_id).ok_or(Error::CarePlanNotFound)?;
+    if has_field(env, &raw, "end_date")? {
+        return Ok(false);
+    }
+    let legacy = LegacyCarePlan::try_from_val(env, &raw).map_err(|_| Error::InvalidInput)?;
+
+    let plan = CarePlan {
+        care_plan_id: legacy.care_plan_id,
+        patient_id: legacy.patient_id,
+        provider_id: legacy.provider_id,
+        plan_type: legacy.plan_type,
+        conditions: legacy.conditions,
+        coded_conditions: Vec::new(env),
+        goals: legacy.goals,
+        start_date: legacy.start_date,
+        review_frequency_days: legacy.review_frequency_days,
+        status: legacy.status,
+        next_review_date: legacy.next_review_date,
+        last_review_date: legacy.last_review_date,
+        end_date: None,
+        created_at: legacy.created_at,
+    };
+    save_care_plan(env, &plan);
+
+    // The original layout predates the provider and condition indexes
+    if !load_provider_plans(env, &plan.provider_id).contains(care_plan_id) {
+        add_provider_plan(env, &plan.provider_id, care_plan_id);
+    }
+    for condition in plan.conditions.iter() {
+        add_condition_plan(env, &condition, care_plan_id);
+    }
+    Ok(true)
+}
+
+fn migrate_intervention_record(env: &Env, intervention_id: u64) -> Result<bool, Error> {
+    let Some(raw) = load_intervention_raw(env, intervention_id) else {
+        return Ok(false);
+    };
+    if has_field(env, &raw, "active")? {
+        return Ok(false);
+    }
+    let legacy = LegacyIntervention::try_from_val(env, &raw).map_err(|_| Error::InvalidInput)?;
+
+    let intervention = Intervention {
+        intervention_id: legacy.intervention_id,
+        care_plan_id: legacy.care_plan_id,
+        intervention_type: legacy.intervention_type,
+        description: legacy.description,
+        frequency: legacy.frequency,
+        responsible_party: legacy.responsible_party,
+        assignee: None,
+        assigned_by: legacy.assigned_by,
+        created_at: legacy.created_at,
+        active: true,
+        goal_id: None,
+    };
+    save_intervention(env, &intervention);
+    Ok(true)
+}
+
+fn migrate_barrier_record(env: &Env, barrier_id: u64) -> Result<bool, Error> {
+    let Some(raw) = load_barrier_raw(env, barrier_id) else {
+        return Ok(false);
+    };
+    if has_field(env, &raw, "reported_by_patient")? {
+        return Ok(false);
+    }
+    let legacy = LegacyBarrier::try_from_val(env, &raw).map_err(|_| Error::InvalidInput)?;
+
+    let barrier = Barrier {
+        barrier_id: legacy.barrier_id,
+        care_plan_id: legacy.care_plan_id,
+        reporter: legacy.reporter,
+        barrier_type: legacy.barrier_type,
+        description: legacy.description,
+        identified_date: legacy.identified_date,
+        resolved: legacy.resolved,
+        resolution: legacy.resolution,
+        resolution_date: legacy.resolution_date,
+        resolved_by: legacy.resolved_by,
+        assigned_to: None,
+        reported_by_patient: false,
+    };
+    save_barrier(env, &barrier);
+    Ok(true)
+}
+
+fn migrate_review_record(env: &Env, review_id: u64) -> Result<bool, Error> {
+    let Some(raw) = load_review_raw(env, review_id) else {
+        return Ok(false);
+    };
+    if has_field(env, &raw, "cancelled")? {
+        return Ok(false);
+    }
+    let legacy = LegacyCareReview::try_from_val(env, &raw).map_err(|_| Error::InvalidInput)?;
+
+    let review = CareReview {
+        review_id: legacy.review_id,
+        care_plan_id: legacy.care_plan_id,
+        scheduled_by: legacy.scheduled_by,
+        review_date: legacy.review_date,
+        review_type: legacy.review_type,
+        review_kind: ReviewKind::Scheduled,
+        conducted: legacy.conducted,
+        review_notes_hash: legacy.review_notes_hash,
+        plan_modifications: legacy.plan_modifications,
+        continue_plan: legacy.continue_plan,
+        conducted_by: legacy.conducted_by,
+        conducted_at: legacy.conducted_at,
+        cancelled: false,
+        cancel_reason: None,
+    };
+    save_review(env, &review);
+    Ok(true)
+}
+
+/// Convert a goal stored in the original layout, identified by its
+/// `outcome_notes` field, and save it with defaults for newer fields.
+fn migrate_goal_record(env: &Env, goal_id: u64) -> Result<bool, Error> {
+    let raw = load_goal_raw(env, goal_id).ok_or(Error::GoalNotFound)?;
+    if !has_field(env, &raw, "outcome_notes")? {
+        return Ok(false);
+    }
+    let legacy = LegacyCareGoal::try_from_val(env, &raw).map_err(|_| Error::InvalidInput)?;
+
+    for (index, entry) in legacy.progress_entries.iter().enumerate() {
+        let migrated = ProgressEntry {
+            goal_id: entry.goal_id,
+            patient_id: entry.patient_id.clone(),
+            recorded_by: entry.patient_id,
+            current_value: entry.current_value,
+            progress_note: entry.progress_note,
+            numeric_value: None,
+            recorded_date: entry.recorded_date,
+            superseded: false,
+            corrects: None,
+        };
+        save_progress_entry(env, goal_id, index as u32, &migrated);
+    }
+
+    if let (Some(date), Some(notes)) = (legacy.achievement_date, &legacy.outcome_notes) {
+        save_goal_outcome(env, goal_id, date, notes);
+    }
+
+    let goal = CareGoal {
+        goal_id: legacy.goal_id,
+        care_plan_id: legacy.care_plan_id,
+        description: legacy.description,
+        target_value: legacy.target_value,
+        target_number: None,
+        scale: 0,
+        unit: Symbol::new(env, ""),
+        target_date: legacy.target_date,
+        priority: legacy.priority,
+        status: legacy.status,
+        progress_count: legacy.progress_entries.len(),
+        achievement_date: legacy.achievement_date,
+        vital_contract: None,
+        vital_metric: None,
+        depends_on: Vec::new(env),
+        voided: false,
+        void_reason: None,
+        derived_from_guideline: None,
+        created_by: legacy.created_by,
+        created_at: legacy.created_at,
+    };
+    save_goal(env, &goal);
+
+    GoalMigrated { goal_id }.publish(env);
+
+    Ok(true)
+}
+
+/// Load a goal that can still be modified, rejecting voided goals.
+fn load_active_goal(env: &Env, goal_id: u64) -> Result<CareGoal, Error> {
+    let goal = load_goal(env, goal_id).ok_or(Error::GoalNotFound)?;
+    if goal.voided {
+        return Err(Error::GoalVoided);
+    }
+    Ok(goal)
+}
+
+/// Whether a goal is neither voided, achieved nor discontinued.
+fn goal_is_open(goal: &CareGoal) -> bool {
+    !goal.voided && !matches!(goal.status, GoalStatus::Achieved | GoalStatus::Discontinued)
+}
+
+/// Snapshot a closed plan into a compact archive record and purge its
+/// detailed per-entity storage.
+fn archive_plan(env: &Env, plan: CarePlan, archived_by: Address) {
+    let care_plan_id = plan.care_plan_id;
+    let mut goals = Vec::new(env);
+    for id in load_plan_goals(env, care_plan_id).iter() {
+        if let Some(g) = load_goal(env, id) {
+            goals.push_back(GoalArchiveSummary {
+                goal_id: g.goal_id,
+                description: g.description,
+                status: g.status,
+                achievement_date: g.achievement_date,
+            });
+        }
+    }
+
+    let mut interventions = Vec::new(env);
+    for id in load_plan_interventions(env, care_plan_id).iter() {
+        if let Some(i) = load_intervention(env, id) {
+            interventions.push_back(InterventionArchiveSummary {
+                intervention_id: i.intervention_id,
+                intervention_type: i.intervention_type,
+                description: i.description,
+            });
+        }
+    }
+
+    let archive = ArchivedPlan {
+        plan,
+        goals,
+        interventions,
+        archived_by: archived_by.clone(),
+        archived_at: env.ledger().timestamp(),
+    };
+
+    save_archive(env, &archive);
+    purge_plan_records(env, care_plan_id);
+
+    CarePlanArchived {
+        care_plan_id,
+        archived_by,
+    }
+    .publish(env);
+}
+
+/// Mark an open barrier resolved and update the plan's barrier stats.
+fn close_barrier(
+    env: &Env,
+    mut barrier: Barrier,
+    provider_id: &Address,
+    resolution: String,
+    resolution_date: u64,
+) {
+    barrier.resolved = true;
+    barrier.resolution = Some(resolution);
+    barrier.resolution_date = Some(resolution_date);
+    barrier.resolved_by = Some(provider_id.clone());
+
+    save_barrier(env, &barrier);
+    update_barrier_stats(env, barrier.care_plan_id, &barrier.barrier_type, true);
+
+    env.events().publish(
+        (Symbol::new(env, "barrier_resolved"),),
+        (barrier.barrier_id, provider_id.clone()),
+    );
+}
+
+/// Fail with GoalLimitExceeded when the plan already holds the maximum
+/// number of goals.
+fn ensure_goal_capacity(env: &Env, care_plan_id: u64) -> Result<(), Error> {
+    let max = get_max_goals_per_plan(env).unwrap_or(DEFAULT_MAX_GOALS_PER_PLAN);
+    if load_plan_goals(env, care_plan_id).len() >= max {
+        return Err(Error::GoalLimitExceeded);
+    }
+    Ok(())
+}
+
+/// Whether an Active plan is past its next review date and grace period.
+fn plan_review_overdue(env: &Env, plan: &CarePlan) -> bool {
+    let grace = get_review_grace_days(env, plan.care_plan_id) as u64 * 86_400;
+    matches!(plan.status, CarePlanStatus::Active)
+        && plan.next_review_date.saturating_add(grace) < env.ledger().timestamp()
+}
+
+/// Whether an open goal is past its target date.
+fn goal_is_overdue(env: &Env, goal: &CareGoal) -> bool {
+    goal_is_open(goal) && goal.target_date < env.ledger().timestamp()
+}
+
+/// Reject progress while any prerequisite goal is not yet achieved.
+/// Voided prerequisites no longer block.
+fn ensure_dependencies_met(env: &Env, goal: &CareGoal) -> Result<(), Error> {
+    for dep_id in goal.depends_on.iter() {
+        match load_goal(env, dep_id) {
+            Some(dep) if dep.voided || matches!(dep.status, GoalStatus::Achieved) => {}
+            _ => return Err(Error::GoalBlocked),
+        }
+    }
+    Ok(())
+}
+
+/// Whether `target` is reachable by following `depends_on` edges from `deps`.
+fn dependencies_reach(env: &Env, deps: &Vec<u64>, target: u64) -> bool {
+    let mut stack = deps.clone();
+    let mut visited: Vec<u64> = Vec::new(env);
+    while let Some(id) = stack.pop_back() {
+        if id == target {
+            return true;
+        }
+        if visited.contains(id) {
+            continue;
+        }
+        visited.push_back(id);
+        if let Some(goal) = load_goal(env, id) {
+            for next in goal.depends_on.iter() {
+                stack.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+/// When consent is required, verify the plan has an unrevoked consent record.
+fn ensure_consent(env: &Env, care_plan_id: u64) -> Result<(), Error> {
+    if !is_consent_required(env) {
+        return Ok(());
+    }
+    match load_plan_consent(env, care_plan_id) {
+        Some(consent) if consent.revoked_at.is_none() => Ok(()),
+        _ => Err(Error::ConsentRequired),
+    }
+}
+
+/// Render a fixed-point value with `scale` decimal places, e.g. 72 at scale
+/// 1 as "7.2".
+fn fixed_to_string(env: &Env, value: i64, scale: u32) -> String {
+    let mut buf = [0u8; 42];
+    let mut start = buf.len();
+    let mut v = value.unsigned_abs();
+    let mut digits = 0u32;
+    loop {
+        if digits == scale && scale > 0 {
+            start -= 1;
+            buf[start] = b'.';
+        }
+        start -= 1;
+        buf[start] = b'0' + (v % 10) as u8;
+        v /= 10;
+        digits += 1;
+        if v == 0 && digits > scale {
+            break;
+        }
+    }
+    if value < 0 {
+        start -= 1;
+        buf[start] = b'-';
+    }
+    String::from_bytes(env, &buf[start..])
+}
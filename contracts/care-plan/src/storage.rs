@@ -1,11 +1,144 @@
 #![no_std]
 
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{Address, BytesN, Env, Map, String, Symbol, Val, Vec};
 
 use crate::types::{
-    Barrier, CarePlan, CareReview, CareTeamMember, CareGoal, DataKey, Intervention,
+    AchievementRetraction, ArchivedPlan, Barrier, CareGoal, CarePlan, CarePlanStatus, CareReview,
+    CareTeamMember, CompletionReport, ConfigKey, DataKey, EmergencyContact, GoalComment,
+    Intervention, InterventionRating, PlanConsent, PlanCosignature, PlanNote, ProgressEntry,
 };
 
+// -----------------------------------------------------------------------
+// Admin / config
+// -----------------------------------------------------------------------
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().persistent().has(&DataKey::Admin)
+}
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().persistent().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::Admin)
+}
+
+pub fn set_version(env: &Env, version: u32) {
+    env.storage().persistent().set(&DataKey::Version, &version);
+}
+
+pub fn get_version(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Version)
+        .unwrap_or(0)
+}
+
+pub fn set_consent_required(env: &Env, required: bool) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ConsentRequired, &required);
+}
+
+pub fn is_consent_required(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ConsentRequired)
+        .unwrap_or(false)
+}
+
+pub fn set_cosign_required(env: &Env, required: bool) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::CosignRequired, &required);
+}
+
+pub fn is_cosign_required(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CosignRequired)
+        .unwrap_or(false)
+}
+
+pub fn set_guideline_contract(env: &Env, contract: &Address) {
+    env.storage()
+        .persistent()
+        .set(&ConfigKey::GuidelineContract, contract);
+}
+
+pub fn load_guideline_contract(env: &Env) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&ConfigKey::GuidelineContract)
+}
+
+pub fn set_archive_retention_days(env: &Env, days: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ArchiveRetentionDays, &days);
+}
+
+pub fn get_archive_retention_days(env: &Env) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ArchiveRetentionDays)
+}
+
+pub fn set_retraction_window(env: &Env, seconds: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RetractionWindow, &seconds);
+}
+
+pub fn get_retraction_window(env: &Env) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::RetractionWindow)
+}
+
+pub fn set_max_goals_per_plan(env: &Env, max: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::MaxGoalsPerPlan, &max);
+}
+
+pub fn get_max_goals_per_plan(env: &Env) -> Option<u32> {
+    env.storage().persistent().get(&DataKey::MaxGoalsPerPlan)
+}
+
+pub fn set_review_grace_days(env: &Env, days: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ReviewGraceDays, &days);
+}
+
+pub fn set_plan_review_grace_days(env: &Env, care_plan_id: u64, days: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanReviewGraceDays(care_plan_id), &days);
+}
+
+/// Grace days for a plan: its own override, else the contract-wide setting.
+pub fn get_review_grace_days(env: &Env, care_plan_id: u64) -> u32 {
+    let storage = env.storage().persistent();
+    storage
+        .get(&DataKey::PlanReviewGraceDays(care_plan_id))
+        .or_else(|| storage.get(&DataKey::ReviewGraceDays))
+        .unwrap_or(0)
+}
+
+pub fn set_single_active_plan(env: &Env, enabled: bool) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SingleActivePlan, &enabled);
+}
+
+pub fn is_single_active_plan(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SingleActivePlan)
+        .unwrap_or(false)
+}
+
 // -----------------------------------------------------------------------
 // Counter helpers
 // -----------------------------------------------------------------------
@@ -91,22 +224,158 @@ pub fn load_care_plan(env: &Env, care_plan_id: u64) -> Option<CarePlan> {
         .get(&DataKey::CarePlan(care_plan_id))
 }
 
-pub fn add_patient_plan(env: &Env, patient_id: &Address, care_plan_id: u64) {
-    let mut ids: Vec<u64> = env
-        .storage()
+pub fn load_care_plan_raw(env: &Env, care_plan_id: u64) -> Option<Val> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CarePlan(care_plan_id))
+}
+
+pub fn load_idempotent_plan(env: &Env, provider_id: &Address, key: &BytesN<32>) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Idempotency(provider_id.clone(), key.clone()))
+}
+
+pub fn save_idempotent_plan(env: &Env, provider_id: &Address, key: &BytesN<32>, care_plan_id: u64) {
+    env.storage().persistent().set(
+        &DataKey::Idempotency(provider_id.clone(), key.clone()),
+        &care_plan_id,
+    );
+}
+
+pub fn append_status_history(env: &Env, care_plan_id: u64, status: &CarePlanStatus) {
+    let mut history = load_status_history(env, care_plan_id);
+    history.push_back((status.clone(), env.ledger().timestamp()));
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanStatusHistory(care_plan_id), &history);
+}
+
+pub fn load_status_history(env: &Env, care_plan_id: u64) -> Vec<(CarePlanStatus, u64)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanStatusHistory(care_plan_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn load_patient_plans(env: &Env, patient_id: &Address) -> Vec<u64> {
+    env.storage()
         .persistent()
         .get(&DataKey::PatientPlans(patient_id.clone()))
-        .unwrap_or(Vec::new(env));
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_patient_plan(env: &Env, patient_id: &Address, care_plan_id: u64) {
+    let mut ids = load_patient_plans(env, patient_id);
     ids.push_back(care_plan_id);
     env.storage()
         .persistent()
         .set(&DataKey::PatientPlans(patient_id.clone()), &ids);
 }
 
+pub fn load_provider_plans(env: &Env, provider_id: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProviderPlans(provider_id.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_provider_plan(env: &Env, provider_id: &Address, care_plan_id: u64) {
+    let mut ids = load_provider_plans(env, provider_id);
+    ids.push_back(care_plan_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ProviderPlans(provider_id.clone()), &ids);
+}
+
+pub fn load_condition_plans(env: &Env, condition: &String) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ConditionIndex(condition.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_condition_plan(env: &Env, condition: &String, care_plan_id: u64) {
+    let mut ids = load_condition_plans(env, condition);
+    if ids.contains(care_plan_id) {
+        return;
+    }
+    ids.push_back(care_plan_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ConditionIndex(condition.clone()), &ids);
+}
+
+pub fn load_condition_code_plans(env: &Env, code: &Symbol) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ConditionCodeIndex(code.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_condition_code_plan(env: &Env, code: &Symbol, care_plan_id: u64) {
+    let mut ids = load_condition_code_plans(env, code);
+    if ids.contains(care_plan_id) {
+        return;
+    }
+    ids.push_back(care_plan_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ConditionCodeIndex(code.clone()), &ids);
+}
+
+pub fn remove_condition_code_plan(env: &Env, code: &Symbol, care_plan_id: u64) {
+    let mut ids = load_condition_code_plans(env, code);
+    if let Some(i) = ids.first_index_of(care_plan_id) {
+        ids.remove(i);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ConditionCodeIndex(code.clone()), &ids);
+    }
+}
+
 // -----------------------------------------------------------------------
 // CareGoal
 // -----------------------------------------------------------------------
 
+pub fn load_provider_goal_stats(env: &Env, provider_id: &Address) -> (u32, u32) {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProviderGoalStats(provider_id.clone()))
+        .unwrap_or((0, 0))
+}
+
+/// Adjust a provider's (achieved, created) goal counters.
+pub fn update_provider_goal_stats(env: &Env, provider_id: &Address, achieved: i32, created: i32) {
+    let (a, c) = load_provider_goal_stats(env, provider_id);
+    let counts = (
+        a.saturating_add_signed(achieved),
+        c.saturating_add_signed(created),
+    );
+    env.storage()
+        .persistent()
+        .set(&DataKey::ProviderGoalStats(provider_id.clone()), &counts);
+}
+
+pub fn load_vital_goals(env: &Env, patient_id: &Address, metric: &Symbol) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VitalGoals(patient_id.clone(), metric.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_vital_goal(env: &Env, patient_id: &Address, metric: &Symbol, goal_id: u64) {
+    let mut ids = load_vital_goals(env, patient_id, metric);
+    if ids.contains(goal_id) {
+        return;
+    }
+    ids.push_back(goal_id);
+    env.storage().persistent().set(
+        &DataKey::VitalGoals(patient_id.clone(), metric.clone()),
+        &ids,
+    );
+}
+
 pub fn save_goal(env: &Env, goal: &CareGoal) {
     env.storage()
         .persistent()
@@ -117,6 +386,44 @@ pub fn load_goal(env: &Env, goal_id: u64) -> Option<CareGoal> {
     env.storage().persistent().get(&DataKey::Goal(goal_id))
 }
 
+/// Load one progress entry of a goal by its position.
+pub fn load_progress_entry(env: &Env, goal_id: u64, index: u32) -> Option<ProgressEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GoalProgress(goal_id, index))
+}
+
+pub fn save_progress_entry(env: &Env, goal_id: u64, index: u32, entry: &ProgressEntry) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalProgress(goal_id, index), entry);
+}
+
+/// Store `entry` as the goal's next progress entry and bump its count,
+/// returning the entry's index. The caller saves the goal.
+pub fn push_progress_entry(env: &Env, goal: &mut CareGoal, entry: &ProgressEntry) -> u32 {
+    let index = goal.progress_count;
+    save_progress_entry(env, goal.goal_id, index, entry);
+    goal.progress_count += 1;
+    index
+}
+
+/// All progress entries of a goal, oldest first.
+pub fn load_goal_progress(env: &Env, goal: &CareGoal) -> Vec<ProgressEntry> {
+    let mut entries = Vec::new(env);
+    for index in 0..goal.progress_count {
+        if let Some(entry) = load_progress_entry(env, goal.goal_id, index) {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
+
+/// Load a goal record without decoding it, so older layouts can be inspected.
+pub fn load_goal_raw(env: &Env, goal_id: u64) -> Option<Val> {
+    env.storage().persistent().get(&DataKey::Goal(goal_id))
+}
+
 pub fn add_plan_goal(env: &Env, care_plan_id: u64, goal_id: u64) {
     let mut ids: Vec<u64> = env
         .storage()
@@ -129,6 +436,16 @@ pub fn add_plan_goal(env: &Env, care_plan_id: u64, goal_id: u64) {
         .set(&DataKey::PlanGoals(care_plan_id), &ids);
 }
 
+pub fn remove_plan_goal(env: &Env, care_plan_id: u64, goal_id: u64) {
+    let mut ids = load_plan_goals(env, care_plan_id);
+    if let Some(index) = ids.first_index_of(goal_id) {
+        ids.remove(index);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanGoals(care_plan_id), &ids);
+}
+
 pub fn load_plan_goals(env: &Env, care_plan_id: u64) -> Vec<u64> {
     env.storage()
         .persistent()
@@ -136,6 +453,68 @@ pub fn load_plan_goals(env: &Env, care_plan_id: u64) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+pub fn save_goal_outcome(env: &Env, goal_id: u64, achievement_date: u64, outcome_notes: &String) {
+    env.storage().persistent().set(
+        &DataKey::GoalOutcome(goal_id),
+        &(achievement_date, outcome_notes.clone()),
+    );
+}
+
+pub fn load_goal_outcome(env: &Env, goal_id: u64) -> Option<(u64, String)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GoalOutcome(goal_id))
+}
+
+pub fn remove_goal_outcome(env: &Env, goal_id: u64) {
+    let storage = env.storage().persistent();
+    storage.remove(&DataKey::GoalOutcome(goal_id));
+    storage.remove(&DataKey::GoalAchievedAt(goal_id));
+}
+
+pub fn save_goal_achieved_at(env: &Env, goal_id: u64, marked_at: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalAchievedAt(goal_id), &marked_at);
+}
+
+pub fn load_goal_achieved_at(env: &Env, goal_id: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GoalAchievedAt(goal_id))
+}
+
+pub fn load_goal_retractions(env: &Env, goal_id: u64) -> Vec<AchievementRetraction> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GoalRetractions(goal_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn append_goal_retraction(env: &Env, goal_id: u64, retraction: &AchievementRetraction) {
+    let mut retractions = load_goal_retractions(env, goal_id);
+    retractions.push_back(retraction.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalRetractions(goal_id), &retractions);
+}
+
+pub fn load_goal_comments(env: &Env, goal_id: u64) -> Vec<GoalComment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GoalComments(goal_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn append_goal_comment(env: &Env, goal_id: u64, comment: &GoalComment) -> u32 {
+    let mut comments = load_goal_comments(env, goal_id);
+    comments.push_back(comment.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalComments(goal_id), &comments);
+    comments.len() - 1
+}
+
 // -----------------------------------------------------------------------
 // Intervention
 // -----------------------------------------------------------------------
@@ -152,6 +531,12 @@ pub fn load_intervention(env: &Env, intervention_id: u64) -> Option<Intervention
         .get(&DataKey::Intervention(intervention_id))
 }
 
+pub fn load_intervention_raw(env: &Env, intervention_id: u64) -> Option<Val> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Intervention(intervention_id))
+}
+
 pub fn add_plan_intervention(env: &Env, care_plan_id: u64, intervention_id: u64) {
     let mut ids: Vec<u64> = env
         .storage()
@@ -171,6 +556,37 @@ pub fn load_plan_interventions(env: &Env, care_plan_id: u64) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+pub fn load_intervention_ratings(env: &Env, intervention_id: u64) -> Vec<InterventionRating> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::InterventionRatings(intervention_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_intervention_rating(env: &Env, intervention_id: u64, rating: &InterventionRating) {
+    let mut ratings = load_intervention_ratings(env, intervention_id);
+    ratings.push_back(rating.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::InterventionRatings(intervention_id), &ratings);
+}
+
+pub fn load_plan_notes(env: &Env, care_plan_id: u64) -> Vec<PlanNote> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanNotes(care_plan_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn append_plan_note(env: &Env, care_plan_id: u64, note: &PlanNote) -> u32 {
+    let mut notes = load_plan_notes(env, care_plan_id);
+    notes.push_back(note.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanNotes(care_plan_id), &notes);
+    notes.len() - 1
+}
+
 // -----------------------------------------------------------------------
 // Barrier
 // -----------------------------------------------------------------------
@@ -187,6 +603,12 @@ pub fn load_barrier(env: &Env, barrier_id: u64) -> Option<Barrier> {
         .get(&DataKey::Barrier(barrier_id))
 }
 
+pub fn load_barrier_raw(env: &Env, barrier_id: u64) -> Option<Val> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Barrier(barrier_id))
+}
+
 pub fn add_plan_barrier(env: &Env, care_plan_id: u64, barrier_id: u64) {
     let mut ids: Vec<u64> = env
         .storage()
@@ -199,19 +621,42 @@ pub fn add_plan_barrier(env: &Env, care_plan_id: u64, barrier_id: u64) {
         .set(&DataKey::PlanBarriers(care_plan_id), &ids);
 }
 
-pub fn load_plan_barriers(env: &Env, care_plan_id: u64) -> Vec<Barrier> {
-    let ids: Vec<u64> = env
-        .storage()
+pub fn load_plan_barrier_ids(env: &Env, care_plan_id: u64) -> Vec<u64> {
+    env.storage()
         .persistent()
         .get(&DataKey::PlanBarriers(care_plan_id))
-        .unwrap_or(Vec::new(env));
-    let mut barriers = Vec::new(env);
-    for id in ids.iter() {
-        if let Some(b) = load_barrier(env, id) {
-            barriers.push_back(b);
-        }
-    }
-    barriers
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn load_barrier_stats(env: &Env, care_plan_id: u64) -> Map<Symbol, (u32, u32)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BarrierStats(care_plan_id))
+        .unwrap_or(Map::new(env))
+}
+
+pub fn save_barrier_stats(env: &Env, care_plan_id: u64, stats: &Map<Symbol, (u32, u32)>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::BarrierStats(care_plan_id), stats);
+}
+
+/// Shift one barrier of `barrier_type` into the open or resolved bucket.
+pub fn update_barrier_stats(
+    env: &Env,
+    care_plan_id: u64,
+    barrier_type: &Symbol,
+    resolved: bool,
+) {
+    let mut stats = load_barrier_stats(env, care_plan_id);
+    let (open, closed) = stats.get(barrier_type.clone()).unwrap_or((0, 0));
+    let counts = if resolved {
+        (open.saturating_sub(1), closed + 1)
+    } else {
+        (open + 1, closed)
+    };
+    stats.set(barrier_type.clone(), counts);
+    save_barrier_stats(env, care_plan_id, &stats);
 }
 
 // -----------------------------------------------------------------------
@@ -230,6 +675,10 @@ pub fn load_review(env: &Env, review_id: u64) -> Option<CareReview> {
         .get(&DataKey::Review(review_id))
 }
 
+pub fn load_review_raw(env: &Env, review_id: u64) -> Option<Val> {
+    env.storage().persistent().get(&DataKey::Review(review_id))
+}
+
 pub fn add_plan_review(env: &Env, care_plan_id: u64, review_id: u64) {
     let mut ids: Vec<u64> = env
         .storage()
@@ -242,6 +691,13 @@ pub fn add_plan_review(env: &Env, care_plan_id: u64, review_id: u64) {
         .set(&DataKey::PlanReviews(care_plan_id), &ids);
 }
 
+pub fn load_plan_reviews(env: &Env, care_plan_id: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanReviews(care_plan_id))
+        .unwrap_or(Vec::new(env))
+}
+
 // -----------------------------------------------------------------------
 // Care team
 // -----------------------------------------------------------------------
@@ -257,4 +713,184 @@ pub fn save_care_team(env: &Env, care_plan_id: u64, team: &Vec<CareTeamMember>)
     env.storage()
         .persistent()
         .set(&DataKey::PlanCareTeam(care_plan_id), team);
-}
\ No newline at end of file
+}
+
+pub fn load_team_member_role(env: &Env, care_plan_id: u64, member: &Address) -> Option<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TeamMemberRole(care_plan_id, member.clone()))
+}
+
+pub fn save_team_member_role(env: &Env, care_plan_id: u64, member: &Address, role: &Symbol) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::TeamMemberRole(care_plan_id, member.clone()), role);
+}
+
+pub fn load_role_permissions(env: &Env, care_plan_id: u64) -> Map<Symbol, Vec<Symbol>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RolePermissions(care_plan_id))
+        .unwrap_or(Map::new(env))
+}
+
+pub fn save_role_permissions(env: &Env, care_plan_id: u64, matrix: &Map<Symbol, Vec<Symbol>>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RolePermissions(care_plan_id), matrix);
+}
+
+// -----------------------------------------------------------------------
+// Clinical guidelines
+// -----------------------------------------------------------------------
+
+pub fn load_plan_guidelines(env: &Env, care_plan_id: u64) -> Vec<String> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanGuidelines(care_plan_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn save_plan_guidelines(env: &Env, care_plan_id: u64, guideline_ids: &Vec<String>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanGuidelines(care_plan_id), guideline_ids);
+}
+
+pub fn load_guideline_ref_count(
+    env: &Env,
+    guideline_contract: &Address,
+    guideline_id: &String,
+) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GuidelineRefCount(
+            guideline_contract.clone(),
+            guideline_id.clone(),
+        ))
+        .unwrap_or(0)
+}
+
+pub fn increment_guideline_ref_count(
+    env: &Env,
+    guideline_contract: &Address,
+    guideline_id: &String,
+) {
+    let count =
+        load_guideline_ref_count(env, guideline_contract, guideline_id).saturating_add(1);
+    env.storage().persistent().set(
+        &DataKey::GuidelineRefCount(guideline_contract.clone(), guideline_id.clone()),
+        &count,
+    );
+}
+
+pub fn save_emergency_contact(env: &Env, contact: &EmergencyContact) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::EmergencyContact(contact.care_plan_id), contact);
+}
+
+pub fn load_emergency_contact(env: &Env, care_plan_id: u64) -> Option<EmergencyContact> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EmergencyContact(care_plan_id))
+}
+
+pub fn save_plan_cosignature(env: &Env, care_plan_id: u64, cosignature: &PlanCosignature) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanCosignature(care_plan_id), cosignature);
+}
+
+pub fn load_plan_cosignature(env: &Env, care_plan_id: u64) -> Option<PlanCosignature> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanCosignature(care_plan_id))
+}
+
+// -----------------------------------------------------------------------
+// Consent
+// -----------------------------------------------------------------------
+
+pub fn save_plan_consent(env: &Env, consent: &PlanConsent) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanConsent(consent.care_plan_id), consent);
+}
+
+pub fn load_plan_consent(env: &Env, care_plan_id: u64) -> Option<PlanConsent> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanConsent(care_plan_id))
+}
+
+// -----------------------------------------------------------------------
+// Completion report
+// -----------------------------------------------------------------------
+
+pub fn save_completion_report(env: &Env, report: &CompletionReport) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanCompletion(report.care_plan_id), report);
+}
+
+pub fn load_completion_report(env: &Env, care_plan_id: u64) -> Option<CompletionReport> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanCompletion(care_plan_id))
+}
+
+// -----------------------------------------------------------------------
+// Archive
+// -----------------------------------------------------------------------
+
+pub fn save_archive(env: &Env, archive: &ArchivedPlan) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Archive(archive.plan.care_plan_id), archive);
+}
+
+pub fn load_archive(env: &Env, care_plan_id: u64) -> Option<ArchivedPlan> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Archive(care_plan_id))
+}
+
+/// Remove a plan's detailed per-entity records and indexes.
+pub fn purge_plan_records(env: &Env, care_plan_id: u64) {
+    let storage = env.storage().persistent();
+
+    for id in load_plan_goals(env, care_plan_id).iter() {
+        if let Some(goal) = load_goal(env, id) {
+            for index in 0..goal.progress_count {
+                storage.remove(&DataKey::GoalProgress(id, index));
+            }
+        }
+        storage.remove(&DataKey::Goal(id));
+        storage.remove(&DataKey::GoalOutcome(id));
+        storage.remove(&DataKey::GoalComments(id));
+        storage.remove(&DataKey::GoalAchievedAt(id));
+        storage.remove(&DataKey::GoalRetractions(id));
+    }
+    for id in load_plan_interventions(env, care_plan_id).iter() {
+        storage.remove(&DataKey::Intervention(id));
+        storage.remove(&DataKey::InterventionRatings(id));
+    }
+    for id in load_plan_barrier_ids(env, care_plan_id).iter() {
+        storage.remove(&DataKey::Barrier(id));
+    }
+    for id in load_plan_reviews(env, care_plan_id).iter() {
+        storage.remove(&DataKey::Review(id));
+    }
+    for member in load_care_team(env, care_plan_id).iter() {
+        storage.remove(&DataKey::TeamMemberRole(care_plan_id, member.team_member));
+    }
+
+    storage.remove(&DataKey::PlanGoals(care_plan_id));
+    storage.remove(&DataKey::PlanInterventions(care_plan_id));
+    storage.remove(&DataKey::PlanBarriers(care_plan_id));
+    storage.remove(&DataKey::PlanReviews(care_plan_id));
+    storage.remove(&DataKey::PlanCareTeam(care_plan_id));
+    storage.remove(&DataKey::PlanReviewGraceDays(care_plan_id));
+    storage.remove(&DataKey::CarePlan(care_plan_id));
+}
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::{Address as _, Ledger as _}, Address, BytesN, Env, String, Symbol, Vec};
+use soroban_sdk::{testutils::{Address as _, Ledger as _}, Address, BytesN, Env, String, Symbol, Vec, vec};
 
 // -----------------------------------------------------------------------
 // Helpers
@@ -15,10 +15,12 @@ fn setup() -> (Env, Address, Address) {
     (env, provider, patient)
 }
 
-fn create_plan(env: &Env, patient: &Address, provider: &Address) -> u64 {
-    let contract_id = env.register(CarePlanContract, ());
-    let client = CarePlanContractClient::new(env, &contract_id);
-
+fn create_plan(
+    env: &Env,
+    client: &CarePlanContractClient,
+    patient: &Address,
+    provider: &Address,
+) -> u64 {
     let mut conditions = Vec::new(env);
     conditions.push_back(String::from_str(env, "Type 2 Diabetes"));
 
@@ -34,6 +36,8 @@ fn create_plan(env: &Env, patient: &Address, provider: &Address) -> u64 {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
 }
 
@@ -59,6 +63,8 @@ fn register_and_create_plan(env: &Env) -> (Address, CarePlanContractClient, u64)
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -90,6 +96,8 @@ fn test_create_care_plan_success() {
             &goals,
             &2_000_000u64,
             &90u32,
+            &None,
+            &None,
         )
         ;
 
@@ -116,6 +124,8 @@ fn test_create_care_plan_increments_ids() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -128,6 +138,8 @@ fn test_create_care_plan_increments_ids() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -155,14 +167,263 @@ fn test_create_care_plan_next_review_date_calculated() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
-    let summary = client.get_care_plan_summary(&1, &provider);
+    let summary = client.get_care_plan_summary(&1, &provider, &0, &0, &0, &0, &0, &0);
     // 1_000_000 + 30 * 86_400 = 3_592_000
     assert_eq!(summary.next_review_date, 1_000_000 + 30 * 86_400);
 }
 
+#[test]
+fn test_create_care_plan_review_date_overflow_rejected() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal 1"));
+
+    let result = client.try_create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "chronic_disease"),
+        &conditions,
+        &goals,
+        &(u64::MAX - 86_400),
+        &u32::MAX,
+        &None,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+
+    // The largest frequency is fine from a realistic start date
+    let plan_id = client.create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "chronic_disease"),
+        &conditions,
+        &goals,
+        &1_000_000u64,
+        &u32::MAX,
+        &None,
+        &None,
+    );
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.next_review_date, 1_000_000 + u32::MAX as u64 * 86_400);
+}
+
+#[test]
+fn test_create_care_plan_validates_conditions() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let create = |conditions: &Vec<String>, goals: &Vec<String>| {
+        client.try_create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            conditions,
+            goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+    };
+
+    let no_goals = Vec::new(&env);
+    assert_eq!(create(&Vec::new(&env), &no_goals), Err(Ok(Error::InvalidInput)));
+
+    let mut conditions = Vec::new(&env);
+    for _ in 0..50 {
+        conditions.push_back(String::from_str(&env, "Condition"));
+    }
+    assert!(create(&conditions, &no_goals).is_ok());
+
+    conditions.push_back(String::from_str(&env, "One too many"));
+    assert_eq!(create(&conditions, &no_goals), Err(Ok(Error::InvalidInput)));
+
+    let mut goals = Vec::new(&env);
+    for _ in 0..51 {
+        goals.push_back(String::from_str(&env, "Goal"));
+    }
+    let one_condition = vec![&env, String::from_str(&env, "Diabetes")];
+    assert_eq!(create(&one_condition, &goals), Err(Ok(Error::InvalidInput)));
+}
+
+#[test]
+fn test_single_active_plan_per_type_mode() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &true);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let duplicate = client.try_create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "chronic_disease"),
+        &vec![&env, String::from_str(&env, "Diabetes")],
+        &Vec::new(&env),
+        &1_000_000u64,
+        &30u32,
+        &None,
+        &None,
+    );
+    assert_eq!(duplicate, Err(Ok(Error::DuplicateActivePlan)));
+
+    // A different plan type, or a different patient, is still allowed
+    client.create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "preventive"),
+        &vec![&env, String::from_str(&env, "Diabetes")],
+        &Vec::new(&env),
+        &1_000_000u64,
+        &30u32,
+        &None,
+        &None,
+    );
+    create_plan(&env, &client, &Address::generate(&env), &provider);
+
+    // Once the first plan is completed a new one may be opened
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &1_500_000u64,
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
+    create_plan(&env, &client, &patient, &provider);
+}
+
+#[test]
+fn test_get_provider_plans_pages_caseload() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let first = create_plan(&env, &client, &patient, &provider);
+    let second = create_plan(&env, &client, &Address::generate(&env), &provider);
+    let third = create_plan(&env, &client, &Address::generate(&env), &provider);
+    create_plan(&env, &client, &patient, &Address::generate(&env));
+
+    let page = client.get_provider_plans(&provider, &0, &2);
+    assert_eq!(page, vec![&env, first, second]);
+    assert_eq!(client.get_provider_plans(&provider, &2, &2), vec![&env, third]);
+    assert_eq!(client.get_provider_plans(&provider, &0, &0).len(), 3);
+}
+
+#[test]
+fn test_list_plans_by_condition() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let diabetes = String::from_str(&env, "Diabetes");
+    let hypertension = String::from_str(&env, "Hypertension");
+    let create = |conditions: Vec<String>| {
+        client.create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &Vec::new(&env),
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+    };
+    let both = create(vec![&env, diabetes.clone(), hypertension.clone(), diabetes.clone()]);
+    let htn_only = create(vec![&env, hypertension.clone()]);
+    let dm_only = create(vec![&env, diabetes.clone()]);
+
+    assert_eq!(client.list_plans_by_condition(&diabetes, &0, &0), vec![&env, both, dm_only]);
+    assert_eq!(client.list_plans_by_condition(&hypertension, &1, &1), vec![&env, htn_only]);
+    assert_eq!(
+        client.list_plans_by_condition(&String::from_str(&env, "Asthma"), &0, &0).len(),
+        0
+    );
+}
+
+#[test]
+fn test_coded_conditions_index() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let e11 = Symbol::new(&env, "E11_9");
+    let i10 = Symbol::new(&env, "I10");
+    let diabetes = Condition {
+        code: e11.clone(),
+        description: String::from_str(&env, "Type 2 diabetes"),
+    };
+    let hypertension = Condition {
+        code: i10.clone(),
+        description: String::from_str(&env, "Hypertension"),
+    };
+    let by_code = |code: &Symbol| client.list_plans_by_condition_code(code, &0, &0);
+
+    let first = create_plan(&env, &client, &patient, &provider);
+    let second = create_plan(&env, &client, &patient, &provider);
+    client.set_coded_conditions(&first, &provider, &vec![&env, diabetes.clone()]);
+    let both = vec![&env, diabetes, hypertension.clone()];
+    client.set_coded_conditions(&second, &provider, &both);
+    assert_eq!(by_code(&e11), vec![&env, first, second]);
+    assert_eq!(by_code(&i10), vec![&env, second]);
+
+    // Replacing the codes drops the plan from indexes it no longer matches
+    client.set_coded_conditions(&first, &provider, &vec![&env, hypertension]);
+    assert_eq!(by_code(&e11), vec![&env, second]);
+    assert_eq!(by_code(&i10), vec![&env, second, first]);
+
+    let ids = vec![&env, first];
+    let plan = client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+    assert_eq!(plan.coded_conditions.get(0).unwrap().code, i10);
+    assert_eq!(plan.conditions.len(), 1);
+
+    let outsider = Address::generate(&env);
+    let result = client.try_set_coded_conditions(&first, &outsider, &Vec::new(&env));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_get_plans_batch() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let first = create_plan(&env, &client, &patient, &provider);
+    let second = create_plan(&env, &client, &Address::generate(&env), &provider);
+
+    let plans = client.get_plans_batch(&vec![&env, second, 999, first]);
+    assert_eq!(plans.len(), 3);
+    assert_eq!(plans.get(0).unwrap().unwrap().care_plan_id, second);
+    assert_eq!(plans.get(1).unwrap(), None);
+    assert_eq!(plans.get(2).unwrap().unwrap().patient_id, patient);
+
+    let mut too_many = Vec::new(&env);
+    for id in 0..51u64 {
+        too_many.push_back(id);
+    }
+    assert_eq!(client.try_get_plans_batch(&too_many), Err(Ok(Error::InvalidInput)));
+}
+
 // -----------------------------------------------------------------------
 // add_care_goal
 // -----------------------------------------------------------------------
@@ -188,6 +449,8 @@ fn test_add_care_goal_success() {
                 &goals,
                 &1_000_000u64,
                 &30u32,
+                &None,
+                &None,
             )
             ;
 
@@ -202,6 +465,7 @@ fn test_add_care_goal_success() {
             &Some(String::from_str(&env, "6.9")),
             &2_000_000u64,
             &Symbol::new(&env, "high"),
+            &None,
         )
         ;
 
@@ -221,11 +485,42 @@ fn test_add_care_goal_plan_not_found() {
         &None,
         &2_000_000u64,
         &Symbol::new(&env, "medium"),
+        &None,
     );
 
     assert!(result.is_err());
 }
 
+#[test]
+fn test_get_goal_with_plan() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Target HbA1c"),
+        &Some(String::from_str(&env, "7.0")),
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    let goal = client.get_goal(&goal_id, &false);
+    assert_eq!(goal.care_plan_id, plan_id);
+    assert_eq!(goal.target_value, Some(String::from_str(&env, "7.0")));
+
+    let (goal, plan) = client.get_goal_with_plan(&goal_id);
+    assert_eq!(goal.goal_id, goal_id);
+    assert_eq!(plan.care_plan_id, plan_id);
+    assert_eq!(plan.patient_id, patient);
+
+    assert_eq!(client.try_get_goal(&999, &true), Err(Ok(Error::GoalNotFound)));
+    assert_eq!(client.try_get_goal_with_plan(&999), Err(Ok(Error::GoalNotFound)));
+}
+
 // -----------------------------------------------------------------------
 // add_intervention
 // -----------------------------------------------------------------------
@@ -251,6 +546,8 @@ fn test_add_intervention_success() {
                 &goals,
                 &1_000_000u64,
                 &30u32,
+                &None,
+                &None,
             )
             ;
 
@@ -265,6 +562,8 @@ fn test_add_intervention_success() {
             &String::from_str(&env, "Metformin 500mg"),
             &String::from_str(&env, "Twice daily"),
             &Symbol::new(&env, "patient"),
+            &None,
+            &None,
         )
         ;
 
@@ -284,137 +583,426 @@ fn test_add_intervention_plan_not_found() {
         &String::from_str(&env, "Walk 30 min"),
         &String::from_str(&env, "Daily"),
         &Symbol::new(&env, "patient"),
+        &None,
+        &None,
     );
 
     assert!(result.is_err());
 }
 
-// -----------------------------------------------------------------------
-// record_goal_progress
-// -----------------------------------------------------------------------
-
 #[test]
-fn test_record_goal_progress_success() {
+fn test_intervention_assignee_task_list() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
+    let nurse = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &nurse,
+        &Symbol::new(&env, "nurse"),
+        &Vec::new(&env),
+    );
 
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
+    let assigned_id = client.add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "education"),
+        &String::from_str(&env, "Insulin technique"),
+        &String::from_str(&env, "Weekly"),
+        &Symbol::new(&env, "provider"),
+        &Some(nurse.clone()),
+        &None,
+    );
+    client.add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "exercise"),
+        &String::from_str(&env, "Walk 30 min"),
+        &String::from_str(&env, "Daily"),
+        &Symbol::new(&env, "patient"),
+        &None,
+        &None,
+    );
 
-    let goal_id = client
-        .add_care_goal(
-            &plan_id,
-            &provider,
-            &String::from_str(&env, "Reduce HbA1c"),
-            &Some(String::from_str(&env, "7.0")),
-            &2_000_000u64,
-            &Symbol::new(&env, "high"),
-        )
-        ;
+    let tasks = client.list_interventions_for_assignee(&plan_id, &nurse);
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks.get(0).unwrap().intervention_id, assigned_id);
 
-    client
-        .record_goal_progress(
-            &goal_id,
-            &patient,
-            &String::from_str(&env, "7.5"),
-            &String::from_str(&env, "Progress noted"),
-            &1_100_000u64,
-        )
-        ;
+    // Assignee must be on the plan's care team
+    let result = client.try_add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "medication"),
+        &String::from_str(&env, "Metformin"),
+        &String::from_str(&env, "Daily"),
+        &Symbol::new(&env, "provider"),
+        &Some(Address::generate(&env)),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
 }
 
 #[test]
-fn test_record_goal_progress_goal_not_found() {
-    let (env, _, patient) = setup();
+fn test_list_interventions_active_filter() {
+    let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let result = client.try_record_goal_progress(
-        &999,
-        &patient,
-        &String::from_str(&env, "7.2"),
-        &String::from_str(&env, "Note"),
-        &1_100_000u64,
-    );
-
-    assert!(result.is_err());
+    let add = |description: &str| {
+        client.add_intervention(
+            &plan_id,
+            &provider,
+            &Symbol::new(&env, "exercise"),
+            &String::from_str(&env, description),
+            &String::from_str(&env, "Daily"),
+            &Symbol::new(&env, "patient"),
+            &None,
+            &None,
+        )
+    };
+    let walking = add("Walk 30 min");
+    let swimming = add("Swim 20 min");
+    let cycling = add("Cycle 15 min");
+
+    client.deactivate_intervention(&swimming, &provider);
+    let result = client.try_deactivate_intervention(&swimming, &provider);
+    assert_eq!(result, Err(Ok(Error::InterventionInactive)));
+    let outsider = Address::generate(&env);
+    let result = client.try_deactivate_intervention(&walking, &outsider);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let all = client.list_interventions(&plan_id, &false, &0, &0);
+    assert_eq!(all.len(), 3);
+    assert!(!all.get(1).unwrap().active);
+
+    let active = client.list_interventions(&plan_id, &true, &0, &0);
+    assert_eq!(active.len(), 2);
+    assert_eq!(active.get(0).unwrap().intervention_id, walking);
+    assert_eq!(active.get(1).unwrap().intervention_id, cycling);
+
+    let page = client.list_interventions(&plan_id, &true, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().intervention_id, cycling);
 }
 
 #[test]
-fn test_record_goal_progress_on_achieved_goal_fails() {
+fn test_interventions_linked_to_goal() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let other_plan = create_plan(&env, &client, &patient, &provider);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
-
-    let plan_id = client
-        .create_care_plan(
-            &patient,
+    let add_goal = |plan_id: &u64| {
+        client.add_care_goal(
+            plan_id,
             &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
+            &String::from_str(&env, "Lower HbA1c"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
         )
-        ;
+    };
+    let goal_id = add_goal(&plan_id);
+    let foreign_goal = add_goal(&other_plan);
 
-    let goal_id = client
-        .add_care_goal(
+    let add = |description: &str, goal_id: Option<u64>| {
+        client.try_add_intervention(
             &plan_id,
             &provider,
-            &String::from_str(&env, "Reduce HbA1c"),
+            &Symbol::new(&env, "medication"),
+            &String::from_str(&env, description),
+            &String::from_str(&env, "Daily"),
+            &Symbol::new(&env, "patient"),
             &None,
-            &2_000_000u64,
-            &Symbol::new(&env, "high"),
+            &goal_id,
         )
-        ;
+    };
+    let metformin = add("Metformin", Some(goal_id)).unwrap().unwrap();
+    add("Statin", None).unwrap().unwrap();
+    let diet = add("Low-carb diet", Some(goal_id)).unwrap().unwrap();
+
+    let linked = client.list_interventions_for_goal(&goal_id);
+    assert_eq!(linked.len(), 2);
+    assert_eq!(linked.get(0).unwrap().intervention_id, metformin);
+    assert_eq!(linked.get(1).unwrap().intervention_id, diet);
+    assert_eq!(linked.get(0).unwrap().goal_id, Some(goal_id));
+
+    // The goal must belong to the same plan
+    assert_eq!(
+        add("Insulin", Some(foreign_goal)),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(add("Insulin", Some(99)), Err(Ok(Error::GoalNotFound)));
+    // A goal with interventions can't be moved away from them
+    let result = client.try_move_goal(&goal_id, &provider, &other_plan);
+    assert_eq!(result, Err(Ok(Error::InvalidDependency)));
+}
 
-    client
-        .mark_goal_achieved(
-            &goal_id,
-            &provider,
-            &1_500_000u64,
-            &String::from_str(&env, "Target met"),
-        )
-        ;
+#[test]
+fn test_rate_intervention_effectiveness() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let result = client.try_record_goal_progress(
-        &goal_id,
+    let intervention_id = client.add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "medication"),
+        &String::from_str(&env, "Metformin 500mg"),
+        &String::from_str(&env, "Twice daily"),
+        &Symbol::new(&env, "patient"),
+        &None,
+        &None,
+    );
+    assert_eq!(client.get_intervention_effectiveness(&intervention_id), None);
+
+    client.rate_intervention(
+        &intervention_id,
         &patient,
-        &String::from_str(&env, "6.9"),
-        &String::from_str(&env, "Update"),
-        &1_600_000u64,
+        &3,
+        &String::from_str(&env, "Some stomach upset"),
+    );
+    client.rate_intervention(
+        &intervention_id,
+        &provider,
+        &4,
+        &String::from_str(&env, "Glucose trending down"),
     );
 
-    assert!(result.is_err());
+    assert_eq!(client.get_intervention_ratings(&intervention_id).len(), 2);
+    assert_eq!(client.get_intervention_effectiveness(&intervention_id), Some(350));
+
+    assert_eq!(
+        client.try_rate_intervention(&intervention_id, &provider, &6, &String::from_str(&env, "")),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_rate_intervention(&999, &provider, &3, &String::from_str(&env, "")),
+        Err(Ok(Error::InterventionNotFound))
+    );
 }
 
 // -----------------------------------------------------------------------
-// mark_goal_achieved
+// attach_guideline
 // -----------------------------------------------------------------------
 
+/// Deploy a guideline contract holding one guideline and trust it from the
+/// care plan contract.
+fn register_guideline(
+    env: &Env,
+    client: &CarePlanContractClient,
+    guideline_id: &String,
+    criteria_hash: &BytesN<32>,
+) -> Address {
+    let guideline_contract = env.register(clinical_guideline::ClinicalGuidelineContract, ());
+    let guidelines =
+        clinical_guideline::ClinicalGuidelineContractClient::new(env, &guideline_contract);
+    let admin = Address::generate(env);
+    guidelines.initialize(&admin);
+    guidelines.register_clinical_guideline(
+        &admin,
+        &clinical_guideline::GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(env, "Hypertension"),
+            criteria_hashes: vec![env, criteria_hash.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: criteria_hash.clone(),
+            recommendation: String::from_str(env, "Start ACE inhibitor"),
+            strength: clinical_guideline::RecommendationStrength::Strong,
+            evidence_level: clinical_guideline::EvidenceLevel::A,
+            alternative_options: Vec::new(env),
+        },
+    );
+    let care_plan_admin = Address::generate(env);
+    client.initialize(&care_plan_admin, &false);
+    client.set_guideline_contract(&care_plan_admin, &guideline_contract);
+    guideline_contract
+}
+
 #[test]
-fn test_mark_goal_achieved_success() {
+fn test_attach_guideline_records_intervention_when_applicable() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let guideline_id = String::from_str(&env, "HTN-01");
+    let criteria = BytesN::from_array(&env, &[7u8; 32]);
+    let guideline_contract = register_guideline(&env, &client, &guideline_id, &criteria);
+
+    let intervention_id = client.attach_guideline(
+        &plan_id,
+        &provider,
+        &guideline_contract,
+        &guideline_id,
+        &vec![&env, criteria.clone()],
+    );
+    assert!(intervention_id.is_some());
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.interventions.len(), 1);
+    let intervention = summary.interventions.get(0).unwrap();
+    assert_eq!(intervention.intervention_type, Symbol::new(&env, "guideline"));
+    assert_eq!(intervention.description, String::from_str(&env, "Start ACE inhibitor"));
+    assert_eq!(client.list_plan_guidelines(&plan_id).len(), 1);
+    assert_eq!(
+        client.guideline_reference_count(&guideline_contract, &guideline_id),
+        1
+    );
+    // Counts are per guideline contract, so a same-named guideline elsewhere
+    // is unaffected
+    let other_contract = Address::generate(&env);
+    assert_eq!(
+        client.guideline_reference_count(&other_contract, &guideline_id),
+        0
+    );
+
+    assert_eq!(
+        client.try_attach_guideline(
+            &plan_id,
+            &provider,
+            &guideline_contract,
+            &guideline_id,
+            &vec![&env, criteria.clone()]
+        ),
+        Err(Ok(Error::GuidelineAlreadyAttached))
+    );
+}
+
+#[test]
+fn test_attach_guideline_not_applicable_links_only() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let guideline_id = String::from_str(&env, "HTN-02");
+    let guideline_contract = register_guideline(
+        &env,
+        &client,
+        &guideline_id,
+        &BytesN::from_array(&env, &[7u8; 32]),
+    );
+
+    let intervention_id = client.attach_guideline(
+        &plan_id,
+        &provider,
+        &guideline_contract,
+        &guideline_id,
+        &vec![&env, BytesN::from_array(&env, &[8u8; 32])],
+    );
+    assert_eq!(intervention_id, None);
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.interventions.len(), 0);
+    assert_eq!(
+        client.list_plan_guidelines(&plan_id).get(0).unwrap(),
+        guideline_id
+    );
+}
+
+#[test]
+fn test_attach_guideline_requires_permitted_provider_and_trusted_contract() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let guideline_id = String::from_str(&env, "HTN-04");
+    let criteria = BytesN::from_array(&env, &[7u8; 32]);
+    let guideline_contract = register_guideline(&env, &client, &guideline_id, &criteria);
+    let attributes = vec![&env, criteria.clone()];
+    let attach = |by: &Address, contract: &Address| {
+        client.try_attach_guideline(&plan_id, by, contract, &guideline_id, &attributes)
+    };
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        attach(&outsider, &guideline_contract),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // Team members need add_intervention once the plan has a matrix
+    let nurse = Address::generate(&env);
+    let nurse_role = Symbol::new(&env, "nurse");
+    client.assign_care_team_member(&plan_id, &provider, &nurse, &nurse_role, &Vec::new(&env));
+    client.set_role_permissions(&plan_id, &provider, &nurse_role, &Vec::new(&env));
+    assert_eq!(
+        attach(&nurse, &guideline_contract),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // Only the configured guideline contract is trusted
+    let untrusted = env.register(clinical_guideline::ClinicalGuidelineContract, ());
+    assert_eq!(
+        attach(&provider, &untrusted),
+        Err(Ok(Error::UntrustedGuidelineContract))
+    );
+    assert!(attach(&provider, &guideline_contract).is_ok());
+}
+
+#[test]
+fn test_goal_records_guideline_provenance() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let guideline_id = String::from_str(&env, "HTN-03");
+    let criteria = BytesN::from_array(&env, &[7u8; 32]);
+    let guideline_contract = register_guideline(&env, &client, &guideline_id, &criteria);
+    let description = String::from_str(&env, "BP below 130/80");
+
+    // Not attached to the plan yet
+    assert_eq!(
+        client.try_add_care_goal(
+            &plan_id,
+            &provider,
+            &description,
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &Some(guideline_id.clone()),
+        ),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    client.attach_guideline(
+        &plan_id,
+        &provider,
+        &guideline_contract,
+        &guideline_id,
+        &vec![&env, criteria],
+    );
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &description,
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &Some(guideline_id.clone()),
+    );
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    let goal = summary.active_goals.get(0).unwrap();
+    assert_eq!(goal.goal_id, goal_id);
+    assert_eq!(goal.derived_from_guideline, Some(guideline_id));
+}
+
+// -----------------------------------------------------------------------
+// record_goal_progress
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_record_goal_progress_success() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
@@ -433,6 +1021,8 @@ fn test_mark_goal_achieved_success() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -440,25 +1030,194 @@ fn test_mark_goal_achieved_success() {
         .add_care_goal(
             &plan_id,
             &provider,
-            &String::from_str(&env, "Target HbA1c"),
-            &None,
+            &String::from_str(&env, "Reduce HbA1c"),
+            &Some(String::from_str(&env, "7.0")),
             &2_000_000u64,
             &Symbol::new(&env, "high"),
+            &None,
         )
         ;
 
     client
-        .mark_goal_achieved(
+        .record_goal_progress(
             &goal_id,
-            &provider,
-            &1_500_000u64,
-            &String::from_str(&env, "Patient reached target"),
+            &patient,
+            &String::from_str(&env, "7.5"),
+            &String::from_str(&env, "Progress noted"),
+            &1_100_000u64,
         )
         ;
 }
 
 #[test]
-fn test_mark_goal_achieved_twice_fails() {
+fn test_record_goal_progress_on_behalf_of_patient() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let nurse = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &nurse,
+        &Symbol::new(&env, "nurse"),
+        &Vec::new(&env),
+    );
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Target HbA1c"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    client.record_goal_progress(
+        &goal_id,
+        &nurse,
+        &String::from_str(&env, "7.4"),
+        &String::from_str(&env, "Recorded at clinic visit"),
+        &1_100_000u64,
+    );
+
+    let entry = client.get_goal_progress(&goal_id).get(0).unwrap();
+    assert_eq!(entry.patient_id, patient);
+    assert_eq!(entry.recorded_by, nurse);
+
+    let result = client.try_record_goal_progress(
+        &goal_id,
+        &Address::generate(&env),
+        &String::from_str(&env, "7.0"),
+        &String::from_str(&env, "Note"),
+        &1_200_000u64,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_record_numeric_progress() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "HbA1c below 7%"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    // Numeric progress needs a numeric target first
+    assert_eq!(
+        client.try_record_numeric_progress(&goal_id, &patient, &72),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    client.set_numeric_target(&goal_id, &provider, &70, &1, &Symbol::new(&env, "pct"));
+    env.ledger().set_timestamp(1_200_000);
+    client.record_numeric_progress(&goal_id, &patient, &72);
+    client.record_numeric_progress(&goal_id, &patient, &-5);
+
+    let goal = client.get_goal(&goal_id, &false);
+    assert_eq!(goal.target_number, Some(70));
+    assert_eq!(goal.unit, Symbol::new(&env, "pct"));
+
+    assert_eq!(goal.progress_count, 2);
+
+    let progress = client.get_goal_progress(&goal_id);
+    let first = progress.get(0).unwrap();
+    assert_eq!(first.numeric_value, Some(72));
+    assert_eq!(first.current_value, String::from_str(&env, "7.2"));
+    assert_eq!(first.recorded_date, 1_200_000);
+    let second = progress.get(1).unwrap();
+    assert_eq!(second.current_value, String::from_str(&env, "-0.5"));
+}
+
+#[test]
+fn test_correct_progress_entry_keeps_original() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Reduce HbA1c"),
+        &Some(String::from_str(&env, "7.0")),
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    client.record_goal_progress(
+        &goal_id,
+        &patient,
+        &String::from_str(&env, "74"),
+        &String::from_str(&env, "Home reading"),
+        &1_100_000u64,
+    );
+
+    let corrected = client.correct_progress_entry(
+        &goal_id,
+        &0,
+        &patient,
+        &String::from_str(&env, "7.4"),
+        &String::from_str(&env, "Missed the decimal point"),
+    );
+    assert_eq!(corrected, 1);
+
+    let entries = client.get_goal_progress(&goal_id);
+    assert_eq!(entries.len(), 2);
+    let original = entries.get(0).unwrap();
+    assert!(original.superseded);
+    assert_eq!(original.current_value, String::from_str(&env, "74"));
+    let correction = entries.get(1).unwrap();
+    assert_eq!(correction.corrects, Some(0));
+    assert_eq!(correction.current_value, String::from_str(&env, "7.4"));
+    assert!(!correction.superseded);
+
+    let reason = String::from_str(&env, "Again");
+    let value = String::from_str(&env, "7.5");
+    // A superseded entry cannot be corrected twice
+    assert_eq!(
+        client.try_correct_progress_entry(&goal_id, &0, &provider, &value, &reason),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_correct_progress_entry(&goal_id, &5, &provider, &value, &reason),
+        Err(Ok(Error::InvalidInput))
+    );
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_correct_progress_entry(&goal_id, &1, &outsider, &value, &reason),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_record_goal_progress_goal_not_found() {
+    let (env, _, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let result = client.try_record_goal_progress(
+        &999,
+        &patient,
+        &String::from_str(&env, "7.2"),
+        &String::from_str(&env, "Note"),
+        &1_100_000u64,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_record_goal_progress_on_achieved_goal_fails() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
@@ -477,6 +1236,8 @@ fn test_mark_goal_achieved_twice_fails() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -484,10 +1245,11 @@ fn test_mark_goal_achieved_twice_fails() {
         .add_care_goal(
             &plan_id,
             &provider,
-            &String::from_str(&env, "Target HbA1c"),
+            &String::from_str(&env, "Reduce HbA1c"),
             &None,
             &2_000_000u64,
             &Symbol::new(&env, "high"),
+            &None,
         )
         ;
 
@@ -496,62 +1258,1591 @@ fn test_mark_goal_achieved_twice_fails() {
             &goal_id,
             &provider,
             &1_500_000u64,
-            &String::from_str(&env, "Done"),
+            &String::from_str(&env, "Target met"),
         )
         ;
 
-    let result = client.try_mark_goal_achieved(
+    let result = client.try_record_goal_progress(
         &goal_id,
-        &provider,
+        &patient,
+        &String::from_str(&env, "6.9"),
+        &String::from_str(&env, "Update"),
         &1_600_000u64,
-        &String::from_str(&env, "Again"),
     );
 
     assert!(result.is_err());
 }
 
 // -----------------------------------------------------------------------
-// add_barrier / resolve_barrier
+// set_goal_dependencies
 // -----------------------------------------------------------------------
 
 #[test]
-fn test_add_barrier_success() {
+fn test_goal_dependency_blocks_progress_until_achieved() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
+    let stabilize = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Stabilize BP"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    let exercise = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Begin exercise program"),
+        &None,
+        &3_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
 
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
+    let mut deps = Vec::new(&env);
+    deps.push_back(stabilize);
+    client.set_goal_dependencies(&exercise, &provider, &deps);
 
-    let barrier_id = client
-        .add_barrier(
-            &plan_id,
+    let record = || {
+        client.try_record_goal_progress(
+            &exercise,
             &patient,
-            &Symbol::new(&env, "financial"),
-            &String::from_str(&env, "Cannot afford medication"),
-            &1_050_000u64,
+            &String::from_str(&env, "10 min"),
+            &String::from_str(&env, "First walk"),
+            &1_600_000u64,
+        )
+    };
+    assert_eq!(record(), Err(Ok(Error::GoalBlocked)));
+
+    client.mark_goal_achieved(
+        &stabilize,
+        &provider,
+        &1_500_000u64,
+        &String::from_str(&env, "BP stable"),
+    );
+    assert!(record().is_ok());
+}
+
+#[test]
+fn test_goal_dependency_cycle_rejected() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let a = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "A"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    let b = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "B"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    let mut self_dep = Vec::new(&env);
+    self_dep.push_back(a);
+    assert_eq!(
+        client.try_set_goal_dependencies(&a, &provider, &self_dep),
+        Err(Ok(Error::InvalidDependency))
+    );
+
+    // b depends on a; a depending on b would close a loop
+    client.set_goal_dependencies(&b, &provider, &self_dep);
+    let mut loop_dep = Vec::new(&env);
+    loop_dep.push_back(b);
+    assert_eq!(
+        client.try_set_goal_dependencies(&a, &provider, &loop_dep),
+        Err(Ok(Error::InvalidDependency))
+    );
+}
+
+// -----------------------------------------------------------------------
+// record_patient_consent / revoke_consent
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_consent_gates_progress_when_required() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    client.set_consent_required(&admin, &true);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Walk daily"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
+
+    let record = |date: u64| {
+        client.try_record_goal_progress(
+            &goal_id,
+            &patient,
+            &String::from_str(&env, "20 min"),
+            &String::from_str(&env, "Walked"),
+            &date,
+        )
+    };
+
+    assert_eq!(record(1_100_000), Err(Ok(Error::ConsentRequired)));
+
+    client.record_patient_consent(
+        &plan_id,
+        &patient,
+        &BytesN::from_array(&env, &[3u8; 32]),
+        &1_000_000u64,
+    );
+    assert!(record(1_100_000).is_ok());
+
+    client.revoke_consent(&plan_id, &patient);
+    assert!(client.get_plan_consent(&plan_id).unwrap().revoked_at.is_some());
+    assert_eq!(record(1_200_000), Err(Ok(Error::ConsentRequired)));
+}
+
+#[test]
+fn test_record_consent_wrong_patient_fails() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let result = client.try_record_patient_consent(
+        &plan_id,
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        &1_000_000u64,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_version_and_upgrade_requires_admin() {
+    let (env, _, _) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.version(), 0);
+    client.initialize(&Address::generate(&env), &false);
+    assert_eq!(client.version(), 1);
+
+    let result = client.try_upgrade(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[0u8; 32]),
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.version(), 1);
+}
+
+#[test]
+fn test_migrate_legacy_goal_layout() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    // A current-layout goal is left alone
+    let current_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Current"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "low"),
+        &None,
+    );
+
+    let legacy = LegacyCareGoal {
+        goal_id: 7,
+        care_plan_id: plan_id,
+        description: String::from_str(&env, "Walk daily"),
+        target_value: None,
+        target_date: 2_000_000,
+        priority: Symbol::new(&env, "medium"),
+        status: GoalStatus::Achieved,
+        progress_entries: vec![
+            &env,
+            LegacyProgressEntry {
+                goal_id: 7,
+                patient_id: patient.clone(),
+                current_value: String::from_str(&env, "5000 steps"),
+                progress_note: String::from_str(&env, "Week one"),
+                recorded_date: 1_100_000,
+            },
+        ],
+        achievement_date: Some(1_500_000),
+        outcome_notes: Some(String::from_str(&env, "Sustained")),
+        created_by: provider.clone(),
+        created_at: 1_000_000,
+    };
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(&DataKey::Goal(7), &legacy);
+    });
+
+    assert_eq!(client.migrate_goals_range(&admin, &1, &10), 1);
+    assert!(!client.migrate_goal(&admin, &7));
+    assert!(!client.migrate_goal(&admin, &current_id));
+
+    let goal = client.get_goal(&7, &false);
+    assert_eq!(goal.status, GoalStatus::Achieved);
+    assert!(!goal.voided);
+    assert_eq!(goal.progress_count, 1);
+    let progress = client.get_goal_progress(&7);
+    assert_eq!(progress.get(0).unwrap().recorded_by, patient);
+    assert_eq!(
+        client.get_goal_outcome(&7),
+        Some((1_500_000, String::from_str(&env, "Sustained")))
+    );
+
+    assert_eq!(
+        client.try_migrate_goals_range(&admin, &1, &100),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_migrate_goal(&Address::generate(&env), &7),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_migrate_legacy_plan_records() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+
+    let plan = LegacyCarePlan {
+        care_plan_id: 9,
+        patient_id: patient.clone(),
+        provider_id: provider.clone(),
+        plan_type: Symbol::new(&env, "chronic_disease"),
+        conditions: vec![&env, String::from_str(&env, "Asthma")],
+        goals: Vec::new(&env),
+        start_date: 1_000_000,
+        review_frequency_days: 30,
+        status: CarePlanStatus::Active,
+        next_review_date: 3_592_000,
+        last_review_date: None,
+        created_at: 1_000_000,
+    };
+    let intervention = LegacyIntervention {
+        intervention_id: 21,
+        care_plan_id: 9,
+        intervention_type: Symbol::new(&env, "education"),
+        description: String::from_str(&env, "Inhaler technique"),
+        frequency: String::from_str(&env, "Once"),
+        responsible_party: Symbol::new(&env, "provider"),
+        assigned_by: provider.clone(),
+        created_at: 1_000_000,
+    };
+    let barrier = LegacyBarrier {
+        barrier_id: 31,
+        care_plan_id: 9,
+        reporter: provider.clone(),
+        barrier_type: Symbol::new(&env, "financial"),
+        description: String::from_str(&env, "Cannot afford inhaler"),
+        identified_date: 1_000_000,
+        resolved: false,
+        resolution: None,
+        resolution_date: None,
+        resolved_by: None,
+    };
+    let review = LegacyCareReview {
+        review_id: 41,
+        care_plan_id: 9,
+        scheduled_by: provider.clone(),
+        review_date: 3_592_000,
+        review_type: Symbol::new(&env, "routine"),
+        conducted: false,
+        review_notes_hash: None,
+        plan_modifications: Vec::new(&env),
+        continue_plan: true,
+        conducted_by: None,
+        conducted_at: None,
+    };
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        storage.set(&DataKey::CarePlan(9), &plan);
+        storage.set(&DataKey::Intervention(21), &intervention);
+        storage.set(&DataKey::PlanInterventions(9), &vec![&env, 21u64]);
+        storage.set(&DataKey::Barrier(31), &barrier);
+        storage.set(&DataKey::PlanBarriers(9), &vec![&env, 31u64]);
+        storage.set(&DataKey::Review(41), &review);
+        storage.set(&DataKey::PlanReviews(9), &vec![&env, 41u64]);
+    });
+
+    assert_eq!(client.migrate_plans_range(&admin, &1, &10), 4);
+    assert_eq!(client.migrate_plan(&admin, &9), 0);
+
+    let ids = vec![&env, 9u64];
+    let migrated = client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+    assert_eq!(migrated.end_date, None);
+    assert_eq!(migrated.coded_conditions.len(), 0);
+    let summary = client.get_care_plan_summary(&9, &provider, &0, &0, &0, &0, &0, &0);
+    assert!(summary.interventions.get(0).unwrap().active);
+    assert!(!summary.barriers.get(0).unwrap().reported_by_patient);
+    let review = client.get_review(&41);
+    assert_eq!(review.review_kind, ReviewKind::Scheduled);
+    assert!(!review.cancelled);
+    // Barrier stats and plan indexes are rebuilt for the migrated plan
+    let financial = Symbol::new(&env, "financial");
+    assert_eq!(
+        client.barrier_stats(&9).get(financial.clone()).unwrap(),
+        (1, 0)
+    );
+    assert_eq!(
+        client.get_provider_plans(&provider, &0, &0),
+        vec![&env, 9u64]
+    );
+    let asthma = String::from_str(&env, "Asthma");
+    assert_eq!(
+        client.list_plans_by_condition(&asthma, &0, &0),
+        vec![&env, 9u64]
+    );
+    let resolution = String::from_str(&env, "Coupon program");
+    client.resolve_barrier(&31, &provider, &resolution, &1_100_000u64);
+    assert_eq!(client.barrier_stats(&9).get(financial).unwrap(), (0, 1));
+
+    assert_eq!(
+        client.try_migrate_plan(&admin, &10),
+        Err(Ok(Error::CarePlanNotFound))
+    );
+    assert_eq!(
+        client.try_migrate_plans_range(&Address::generate(&env), &1, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_set_consent_required_non_admin_fails() {
+    let (env, _, _) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    client.initialize(&Address::generate(&env), &false);
+    let result = client.try_set_consent_required(&Address::generate(&env), &true);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// -----------------------------------------------------------------------
+// move_goal
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_move_goal_between_patient_plans() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let source = create_plan(&env, &client, &patient, &provider);
+    let target = create_plan(&env, &client, &patient, &provider);
+    let other_patient = create_plan(&env, &client, &Address::generate(&env), &provider);
+
+    let goal_id = client.add_care_goal(
+        &source,
+        &provider,
+        &String::from_str(&env, "Walk daily"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
+
+    assert_eq!(
+        client.try_move_goal(&goal_id, &provider, &other_patient),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.move_goal(&goal_id, &provider, &target);
+
+    assert_eq!(client.get_goal(&goal_id, &false).care_plan_id, target);
+    let source_summary = client.get_care_plan_summary(&source, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(source_summary.active_goals.len(), 0);
+    let target_summary = client.get_care_plan_summary(&target, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(target_summary.active_goals.get(0).unwrap().goal_id, goal_id);
+}
+
+#[test]
+fn test_goal_limit_per_plan() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    let full = create_plan(&env, &client, &patient, &provider);
+    let other = create_plan(&env, &client, &patient, &provider);
+
+    let result = client.try_set_max_goals_per_plan(&admin, &0u32);
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+    client.set_max_goals_per_plan(&admin, &2u32);
+
+    let add_goal = |plan_id: &u64| {
+        client.try_add_care_goal(
+            plan_id,
+            &provider,
+            &String::from_str(&env, "Goal"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "medium"),
+            &None,
+        )
+    };
+    assert!(add_goal(&full).is_ok());
+    assert!(add_goal(&full).is_ok());
+    assert_eq!(add_goal(&full), Err(Ok(Error::GoalLimitExceeded)));
+
+    // Moving a goal into a full plan is refused as well
+    let movable = add_goal(&other).unwrap().unwrap();
+    let result = client.try_move_goal(&movable, &provider, &full);
+    assert_eq!(result, Err(Ok(Error::GoalLimitExceeded)));
+}
+
+// -----------------------------------------------------------------------
+// set_goal_priority
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_set_goal_priority() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Quit smoking"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "low"),
+        &None,
+    );
+
+    client.set_goal_priority(&goal_id, &provider, &Symbol::new(&env, "high"));
+    assert_eq!(client.get_goal(&goal_id, &false).priority, Symbol::new(&env, "high"));
+
+    assert_eq!(
+        client.try_set_goal_priority(&goal_id, &provider, &Symbol::new(&env, "urgent")),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_set_goal_priority(&goal_id, &Address::generate(&env), &Symbol::new(&env, "low")),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+// -----------------------------------------------------------------------
+// void_goal
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_void_goal_hides_it_but_keeps_audit_record() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Entered in error"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "low"),
+        &None,
+    );
+
+    let reason = String::from_str(&env, "Duplicate goal");
+    client.void_goal(&goal_id, &provider, &reason);
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.active_goals.len(), 0);
+    assert_eq!(client.try_get_goal(&goal_id, &false), Err(Ok(Error::GoalNotFound)));
+    assert_eq!(client.try_get_goal_progress(&goal_id), Err(Ok(Error::GoalNotFound)));
+
+    let audited = client.get_goal(&goal_id, &true);
+    assert!(audited.voided);
+    assert_eq!(audited.void_reason, Some(reason.clone()));
+
+    assert_eq!(
+        client.try_record_goal_progress(
+            &goal_id,
+            &patient,
+            &String::from_str(&env, "7.0"),
+            &String::from_str(&env, "note"),
+            &1_500_000u64,
+        ),
+        Err(Ok(Error::GoalVoided))
+    );
+    assert_eq!(
+        client.try_void_goal(&goal_id, &provider, &reason),
+        Err(Ok(Error::GoalVoided))
+    );
+}
+
+// -----------------------------------------------------------------------
+// overdue goals
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_overdue_goals() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let add_goal = |target_date: u64| {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Goal"),
+            &None,
+            &target_date,
+            &Symbol::new(&env, "medium"),
+            &None,
+        )
+    };
+    let late = add_goal(1_500_000);
+    let achieved = add_goal(1_500_000);
+    let future = add_goal(3_000_000);
+
+    client.mark_goal_achieved(
+        &achieved,
+        &provider,
+        &1_400_000u64,
+        &String::from_str(&env, "Met"),
+    );
+
+    env.ledger().set_timestamp(2_000_000);
+
+    assert!(client.is_goal_overdue(&late));
+    assert!(!client.is_goal_overdue(&achieved));
+    assert!(!client.is_goal_overdue(&future));
+    assert!(!client.is_goal_overdue(&999));
+
+    let overdue = client.list_overdue_goals(&plan_id);
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue.get(0).unwrap(), late);
+}
+
+// -----------------------------------------------------------------------
+// mark_goal_achieved
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_mark_goal_achieved_success() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let goal_id = client
+        .add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Target HbA1c"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+        ;
+
+    client
+        .mark_goal_achieved(
+            &goal_id,
+            &provider,
+            &1_500_000u64,
+            &String::from_str(&env, "Patient reached target"),
+        )
+        ;
+}
+
+#[test]
+fn test_mark_goal_achieved_twice_fails() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let goal_id = client
+        .add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Target HbA1c"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+        ;
+
+    client
+        .mark_goal_achieved(
+            &goal_id,
+            &provider,
+            &1_500_000u64,
+            &String::from_str(&env, "Done"),
+        )
+        ;
+
+    let result = client.try_mark_goal_achieved(
+        &goal_id,
+        &provider,
+        &1_600_000u64,
+        &String::from_str(&env, "Again"),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_mark_goal_achieved_stores_outcome_separately() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (provider, client, plan_id) = register_and_create_plan(&env);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Lower BP"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    assert_eq!(client.get_goal_outcome(&goal_id), None);
+
+    client.mark_goal_achieved(
+        &goal_id,
+        &provider,
+        &1_500_000u64,
+        &String::from_str(&env, "BP stable at 125/78"),
+    );
+
+    let (achieved_at, notes) = client.get_goal_outcome(&goal_id).unwrap();
+    assert_eq!(achieved_at, 1_500_000);
+    assert_eq!(notes, String::from_str(&env, "BP stable at 125/78"));
+}
+
+#[test]
+fn test_mark_goal_achieved_unrelated_provider_fails() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Target HbA1c"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    let other_provider = Address::generate(&env);
+    let result = client.try_mark_goal_achieved(
+        &goal_id,
+        &other_provider,
+        &1_500_000u64,
+        &String::from_str(&env, "Closed by another clinic"),
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_mark_goal_achieved_by_care_team_member() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Target HbA1c"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+
+    let physician = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &physician,
+        &Symbol::new(&env, "physician"),
+        &Vec::new(&env),
+    );
+
+    client.mark_goal_achieved(
+        &goal_id,
+        &physician,
+        &1_500_000u64,
+        &String::from_str(&env, "Target reached"),
+    );
+    assert!(client.get_goal_outcome(&goal_id).is_some());
+}
+
+#[test]
+fn test_provider_achievement_rate() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    assert_eq!(client.provider_achievement_rate(&provider), (0, 0));
+
+    let add_goal = |description: &str| {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, description),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+    };
+    let hba1c = add_goal("Target HbA1c");
+    let weight = add_goal("Lose 5kg");
+    let error = add_goal("Duplicate entry");
+    add_goal("Walk daily");
+    assert_eq!(client.provider_achievement_rate(&provider), (0, 4));
+
+    // Credit goes to the goal's creator, whoever closes it
+    let physician = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &physician,
+        &Symbol::new(&env, "physician"),
+        &Vec::new(&env),
+    );
+    let notes = String::from_str(&env, "Target reached");
+    client.mark_goal_achieved(&hba1c, &physician, &1_500_000u64, &notes);
+    client.mark_goal_achieved(&weight, &provider, &1_500_000u64, &notes);
+    assert_eq!(client.provider_achievement_rate(&provider), (2, 4));
+    assert_eq!(client.provider_achievement_rate(&physician), (0, 0));
+
+    // Voided goals drop out of both counts
+    let reason = String::from_str(&env, "Entered in error");
+    client.void_goal(&error, &provider, &reason);
+    client.void_goal(&weight, &provider, &reason);
+    assert_eq!(client.provider_achievement_rate(&provider), (1, 2));
+}
+
+#[test]
+fn test_retract_achievement_within_window() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let add_goal = || {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Target HbA1c"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+    };
+    let goal_id = add_goal();
+    let notes = String::from_str(&env, "Wrong patient chart");
+
+    env.ledger().set_timestamp(10_000);
+    client.mark_goal_achieved(&goal_id, &provider, &9_000u64, &notes);
+    assert_eq!(client.provider_achievement_rate(&provider), (1, 1));
+
+    // Still within the default 24h window
+    env.ledger().set_timestamp(10_000 + 86_400);
+    client.retract_achievement(&goal_id, &provider);
+    let goal = client.get_goal(&goal_id, &false);
+    assert_eq!(goal.status, GoalStatus::Active);
+    assert_eq!(goal.achievement_date, None);
+    assert_eq!(client.get_goal_outcome(&goal_id), None);
+    assert_eq!(client.provider_achievement_rate(&provider), (0, 1));
+
+    let log = client.get_goal_retractions(&goal_id);
+    assert_eq!(log.len(), 1);
+    assert_eq!(log.get(0).unwrap().retracted_by, provider);
+    assert_eq!(log.get(0).unwrap().achievement_date, 9_000);
+    assert_eq!(log.get(0).unwrap().outcome_notes, notes);
+
+    // Not achieved any more, so nothing to retract
+    let result = client.try_retract_achievement(&goal_id, &provider);
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+
+    // A shorter configured window closes sooner
+    client.set_retraction_window(&admin, &3_600u64);
+    let other = add_goal();
+    client.mark_goal_achieved(&other, &provider, &9_000u64, &notes);
+    env.ledger().set_timestamp(10_000 + 86_400 + 3_601);
+    let result = client.try_retract_achievement(&other, &provider);
+    assert_eq!(result, Err(Ok(Error::RetractionWindowClosed)));
+    let outsider = Address::generate(&env);
+    let result = client.try_retract_achievement(&other, &outsider);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// -----------------------------------------------------------------------
+// link_goal_to_vital / sync_goal_from_vitals
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_sync_goal_from_vitals_records_progress() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let vitals_id = env.register(patient_vitals::PatientVitalsContract, ());
+    let vitals = patient_vitals::PatientVitalsContractClient::new(&env, &vitals_id);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Resting heart rate below 80"),
+        &Some(String::from_str(&env, "80")),
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
+
+    let heart_rate = Symbol::new(&env, "heart_rate");
+    client.link_goal_to_vital(&goal_id, &provider, &vitals_id, &heart_rate);
+
+    vitals.record_vital_signs(
+        &patient,
+        &provider,
+        &1_100_000u64,
+        &patient_vitals::VitalSigns {
+            blood_pressure_systolic: None,
+            blood_pressure_diastolic: None,
+            heart_rate: Some(76),
+            temperature: None,
+            respiratory_rate: None,
+            oxygen_saturation: None,
+            blood_glucose: None,
+            weight: None,
+        },
+    );
+
+    client.sync_goal_from_vitals(&goal_id);
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    let goal = summary.active_goals.get(0).unwrap();
+    assert_eq!(goal.progress_count, 1);
+    let entry = client.get_goal_progress(&goal.goal_id).get(0).unwrap();
+    assert_eq!(entry.current_value, String::from_str(&env, "76"));
+    assert_eq!(entry.recorded_date, 1_100_000);
+    assert_eq!(entry.patient_id, patient);
+}
+
+#[test]
+fn test_sync_goal_from_vitals_requires_link_and_reading() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let vitals_id = env.register(patient_vitals::PatientVitalsContract, ());
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Weight below 90kg"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
+
+    assert_eq!(
+        client.try_sync_goal_from_vitals(&goal_id),
+        Err(Ok(Error::VitalLinkNotSet))
+    );
+
+    client.link_goal_to_vital(&goal_id, &provider, &vitals_id, &Symbol::new(&env, "weight"));
+    assert_eq!(
+        client.try_sync_goal_from_vitals(&goal_id),
+        Err(Ok(Error::VitalReadingNotFound))
+    );
+}
+
+#[test]
+fn test_vital_sync_requires_provider_and_consent() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let vitals_id = env.register(patient_vitals::PatientVitalsContract, ());
+    let vitals = patient_vitals::PatientVitalsContractClient::new(&env, &vitals_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    client.set_consent_required(&admin, &true);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Resting heart rate below 80"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
+
+    let heart_rate = Symbol::new(&env, "heart_rate");
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_link_goal_to_vital(&goal_id, &outsider, &vitals_id, &heart_rate),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.link_goal_to_vital(&goal_id, &provider, &vitals_id, &heart_rate);
+
+    vitals.record_vital_signs(
+        &patient,
+        &provider,
+        &1_100_000u64,
+        &patient_vitals::VitalSigns {
+            blood_pressure_systolic: None,
+            blood_pressure_diastolic: None,
+            heart_rate: Some(76),
+            temperature: None,
+            respiratory_rate: None,
+            oxygen_saturation: None,
+            blood_glucose: None,
+            weight: None,
+        },
+    );
+
+    // Without consent neither sync path writes progress
+    assert_eq!(
+        client.try_sync_goal_from_vitals(&goal_id),
+        Err(Ok(Error::ConsentRequired))
+    );
+    let readings = vec![&env, (heart_rate.clone(), 76i64)];
+    assert_eq!(
+        client.record_vital_progress(&vitals_id, &patient, &1_100_000u64, &readings),
+        0
+    );
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 0);
+
+    client.record_patient_consent(
+        &plan_id,
+        &patient,
+        &BytesN::from_array(&env, &[3u8; 32]),
+        &1_000_000u64,
+    );
+    client.sync_goal_from_vitals(&goal_id);
+    assert_eq!(
+        client.record_vital_progress(&vitals_id, &patient, &1_100_000u64, &readings),
+        1
+    );
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 2);
+}
+
+#[test]
+fn test_recording_vitals_updates_linked_goals() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let vitals_id = env.register(patient_vitals::PatientVitalsContract, ());
+    let vitals = patient_vitals::PatientVitalsContractClient::new(&env, &vitals_id);
+
+    let admin = Address::generate(&env);
+    vitals.initialize(&admin);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Systolic below 130"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    let systolic = Symbol::new(&env, "bp_systolic");
+    client.link_goal_to_vital(&goal_id, &provider, &vitals_id, &systolic);
+
+    let mut reading = patient_vitals::VitalSigns {
+        blood_pressure_systolic: Some(142),
+        blood_pressure_diastolic: Some(90),
+        heart_rate: None,
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: None,
+        weight: None,
+    };
+
+    // Not wired up yet, so the goal is untouched
+    vitals.record_vital_signs(&patient, &provider, &1_100_000u64, &reading);
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 0);
+
+    vitals.set_care_plan_link(&admin, &contract_id, &true);
+    // Readings from someone outside the patient's care are stored but not forwarded
+    let outsider = Address::generate(&env);
+    vitals.record_vital_signs(&patient, &outsider, &1_150_000u64, &reading);
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 0);
+
+    vitals.set_provider_authorized(&admin, &provider, &true);
+    vitals.record_vital_signs(&patient, &provider, &1_200_000u64, &reading);
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 1);
+    let entry = client.get_goal_progress(&goal_id).get(0).unwrap();
+    assert_eq!(entry.numeric_value, Some(142));
+    assert_eq!(entry.current_value, String::from_str(&env, "142"));
+    assert_eq!(entry.recorded_by, vitals_id);
+    assert_eq!(entry.recorded_date, 1_200_000);
+
+    // Disabling the link stops further updates
+    vitals.set_care_plan_link(&admin, &contract_id, &false);
+    reading.blood_pressure_systolic = Some(128);
+    vitals.record_vital_signs(&patient, &provider, &1_300_000u64, &reading);
+    assert_eq!(client.get_goal(&goal_id, &false).progress_count, 1);
+}
+
+// -----------------------------------------------------------------------
+// add_barrier / resolve_barrier
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_add_barrier_success() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let barrier_id = client
+        .add_barrier(
+            &plan_id,
+            &patient,
+            &Symbol::new(&env, "financial"),
+            &String::from_str(&env, "Cannot afford medication"),
+            &1_050_000u64,
+        )
+        ;
+
+    assert_eq!(barrier_id, 1);
+}
+
+#[test]
+fn test_patient_reported_barrier_is_tagged() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let transport = Symbol::new(&env, "transportation");
+    let description = String::from_str(&env, "No ride to clinic");
+    let by_patient =
+        client.report_patient_barrier(&plan_id, &patient, &transport, &description, &1_050_000u64);
+    let by_provider =
+        client.add_barrier(&plan_id, &provider, &transport, &description, &1_050_000u64);
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    let patient_report = summary.barriers.get(0).unwrap();
+    assert_eq!(patient_report.barrier_id, by_patient);
+    assert!(patient_report.reported_by_patient);
+    let provider_report = summary.barriers.get(1).unwrap();
+    assert_eq!(provider_report.barrier_id, by_provider);
+    assert!(!provider_report.reported_by_patient);
+
+    // Only the plan's own patient may use the patient path
+    let stranger = Address::generate(&env);
+    let result =
+        client.try_report_patient_barrier(&plan_id, &stranger, &transport, &description, &0u64);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_add_barrier(&plan_id, &stranger, &transport, &description, &0u64);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_resolve_barrier_success() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let barrier_id = client
+        .add_barrier(
+            &plan_id,
+            &patient,
+            &Symbol::new(&env, "financial"),
+            &String::from_str(&env, "Cannot afford medication"),
+            &1_050_000u64,
+        )
+        ;
+
+    client
+        .resolve_barrier(
+            &barrier_id,
+            &provider,
+            &String::from_str(&env, "Enrolled in assistance program"),
+            &1_100_000u64,
+        )
+        ;
+}
+
+#[test]
+fn test_resolve_barrier_twice_fails() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let barrier_id = client
+        .add_barrier(
+            &plan_id,
+            &patient,
+            &Symbol::new(&env, "financial"),
+            &String::from_str(&env, "Cannot afford medication"),
+            &1_050_000u64,
+        )
+        ;
+
+    client
+        .resolve_barrier(
+            &barrier_id,
+            &provider,
+            &String::from_str(&env, "Resolved"),
+            &1_100_000u64,
+        )
+        ;
+
+    let result = client.try_resolve_barrier(
+        &barrier_id,
+        &provider,
+        &String::from_str(&env, "Again"),
+        &1_200_000u64,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_resolve_barrier_outsider_fails() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let barrier_id = client.add_barrier(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "transport"),
+        &String::from_str(&env, "No ride"),
+        &1_000_000u64,
+    );
+
+    let result = client.try_resolve_barrier(
+        &barrier_id,
+        &Address::generate(&env),
+        &String::from_str(&env, "Closed"),
+        &1_100_000u64,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let transport = Symbol::new(&env, "transport");
+    assert_eq!(
+        client.barrier_stats(&plan_id).get(transport).unwrap(),
+        (1, 0)
+    );
+}
+
+#[test]
+fn test_barrier_stats_by_type() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let financial = Symbol::new(&env, "financial");
+    let transport = Symbol::new(&env, "transport");
+    let add = |barrier_type: &Symbol| {
+        client.add_barrier(
+            &plan_id,
+            &provider,
+            barrier_type,
+            &String::from_str(&env, "Barrier"),
+            &1_000_000u64,
+        )
+    };
+    let first = add(&financial);
+    add(&financial);
+    add(&transport);
+
+    client.resolve_barrier(
+        &first,
+        &provider,
+        &String::from_str(&env, "Assistance program"),
+        &1_100_000u64,
+    );
+
+    let stats = client.barrier_stats(&plan_id);
+    assert_eq!(stats.get(financial).unwrap(), (1, 1));
+    assert_eq!(stats.get(transport).unwrap(), (1, 0));
+    assert_eq!(client.barrier_stats(&999).len(), 0);
+}
+
+#[test]
+fn test_resolve_barriers_batch_skips_resolved() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let transport = Symbol::new(&env, "transport");
+    let add = || {
+        client.add_barrier(
+            &plan_id,
+            &provider,
+            &transport,
+            &String::from_str(&env, "No ride"),
+            &1_000_000u64,
+        )
+    };
+    let first = add();
+    let second = add();
+    let third = add();
+
+    let resolution = String::from_str(&env, "Enrolled in ride program");
+    client.resolve_barrier(&second, &provider, &resolution, &1_050_000u64);
+
+    let ids = vec![&env, first, second, third];
+    let resolved = client.resolve_barriers_batch(&ids, &provider, &resolution, &1_100_000u64);
+    assert_eq!(resolved, 2);
+    assert_eq!(
+        client
+            .barrier_stats(&plan_id)
+            .get(transport.clone())
+            .unwrap(),
+        (0, 3)
+    );
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(
+        summary.barriers.get(0).unwrap().resolution_date,
+        Some(1_100_000)
+    );
+    // The earlier resolution is left untouched
+    assert_eq!(
+        summary.barriers.get(1).unwrap().resolution_date,
+        Some(1_050_000)
+    );
+
+    assert_eq!(
+        client.resolve_barriers_batch(&ids, &provider, &resolution, &1_200_000u64),
+        0
+    );
+    let result =
+        client.try_resolve_barriers_batch(&vec![&env, 999], &provider, &resolution, &1_200_000u64);
+    assert_eq!(result, Err(Ok(Error::BarrierNotFound)));
+
+    // A barrier on someone else's plan fails the whole batch
+    let other_provider = Address::generate(&env);
+    let other_plan = create_plan(&env, &client, &Address::generate(&env), &other_provider);
+    let foreign = client.add_barrier(
+        &other_plan,
+        &other_provider,
+        &transport,
+        &String::from_str(&env, "No ride"),
+        &1_000_000u64,
+    );
+    let fourth = add();
+    let ids = vec![&env, fourth, foreign];
+    let result = client.try_resolve_barriers_batch(&ids, &provider, &resolution, &1_200_000u64);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(
+        client.barrier_stats(&plan_id).get(transport).unwrap(),
+        (1, 3)
+    );
+}
+
+#[test]
+fn test_assign_barrier_to_team_member() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let social_worker = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &social_worker,
+        &Symbol::new(&env, "social_worker"),
+        &Vec::new(&env),
+    );
+
+    let transport = client.add_barrier(
+        &plan_id,
+        &patient,
+        &Symbol::new(&env, "transport"),
+        &String::from_str(&env, "No ride to clinic"),
+        &1_050_000u64,
+    );
+    let cost = client.add_barrier(
+        &plan_id,
+        &patient,
+        &Symbol::new(&env, "financial"),
+        &String::from_str(&env, "Cannot afford test strips"),
+        &1_050_000u64,
+    );
+
+    client.assign_barrier(&transport, &provider, &social_worker);
+    let assigned = client.list_barriers_for_assignee(&plan_id, &social_worker);
+    assert_eq!(assigned.len(), 1);
+    let barrier = assigned.get(0).unwrap();
+    assert_eq!(barrier.barrier_id, transport);
+    assert_eq!(barrier.assigned_to, Some(social_worker.clone()));
+
+    // Assignee must be on the care team, and only care providers may assign
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_assign_barrier(&cost, &provider, &outsider),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_assign_barrier(&cost, &outsider, &social_worker),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.resolve_barrier(
+        &cost,
+        &provider,
+        &String::from_str(&env, "Enrolled in assistance program"),
+        &1_060_000u64,
+    );
+    assert_eq!(
+        client.try_assign_barrier(&cost, &provider, &social_worker),
+        Err(Ok(Error::BarrierAlreadyResolved))
+    );
+}
+
+#[test]
+fn test_add_barrier_plan_not_found() {
+    let (env, _, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let result = client.try_add_barrier(
+        &999,
+        &patient,
+        &Symbol::new(&env, "social"),
+        &String::from_str(&env, "No transport"),
+        &1_000_000u64,
+    );
+
+    assert!(result.is_err());
+}
+
+// -----------------------------------------------------------------------
+// schedule_care_plan_review / conduct_care_plan_review
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_schedule_review_success() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+        ;
+
+    let review_id = client
+        .schedule_care_plan_review(
+            &plan_id,
+            &provider,
+            &3_600_000u64,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
+        )
+        ;
+
+    assert_eq!(review_id, 1);
+}
+
+#[test]
+fn test_conduct_review_success() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let mut conditions = Vec::new(&env);
+    conditions.push_back(String::from_str(&env, "Diabetes"));
+    let mut goals = Vec::new(&env);
+    goals.push_back(String::from_str(&env, "Goal"));
+
+    let plan_id = client
+        .create_care_plan(
+            &patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &conditions,
+            &goals,
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
         )
         ;
 
-    assert_eq!(barrier_id, 1);
+    let review_id = client
+        .schedule_care_plan_review(
+            &plan_id,
+            &provider,
+            &3_600_000u64,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
+        )
+        ;
+
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let mut mods = Vec::new(&env);
+    mods.push_back(String::from_str(&env, "Increase exercise frequency"));
+
+    client
+        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true, &false)
+        ;
 }
 
 #[test]
-fn test_resolve_barrier_success() {
+fn test_conduct_review_twice_fails() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
@@ -570,32 +2861,110 @@ fn test_resolve_barrier_success() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
-    let barrier_id = client
-        .add_barrier(
+    let review_id = client
+        .schedule_care_plan_review(
             &plan_id,
-            &patient,
-            &Symbol::new(&env, "financial"),
-            &String::from_str(&env, "Cannot afford medication"),
-            &1_050_000u64,
+            &provider,
+            &3_600_000u64,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
         )
         ;
 
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let mods = Vec::new(&env);
+
     client
-        .resolve_barrier(
-            &barrier_id,
-            &provider,
-            &String::from_str(&env, "Enrolled in assistance program"),
-            &1_100_000u64,
-        )
+        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true, &false)
         ;
+
+    let result =
+        client.try_conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true, &false);
+
+    assert!(result.is_err());
 }
 
 #[test]
-fn test_resolve_barrier_twice_fails() {
+fn test_conduct_review_rejects_blank_or_excess_modifications() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &3_600_000u64,
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let conduct = |mods: &Vec<String>| {
+        client.try_conduct_care_plan_review(&review_id, &provider, &hash, mods, &true, &false)
+    };
+
+    let blank = vec![
+        &env,
+        String::from_str(&env, "Increase metformin"),
+        String::from_str(&env, ""),
+    ];
+    assert_eq!(conduct(&blank), Err(Ok(Error::InvalidInput)));
+
+    let mut too_many = Vec::new(&env);
+    for _ in 0..51 {
+        too_many.push_back(String::from_str(&env, "Adjust dose"));
+    }
+    assert_eq!(conduct(&too_many), Err(Ok(Error::InvalidInput)));
+
+    // A rejected attempt leaves the review open
+    assert!(!client.get_review(&review_id).conducted);
+    let valid = vec![&env, String::from_str(&env, "Increase metformin")];
+    assert!(conduct(&valid).is_ok());
+    assert_eq!(client.get_review(&review_id).plan_modifications, valid);
+}
+
+#[test]
+fn test_review_requires_care_provider() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let outsider = Address::generate(&env);
+    let routine = Symbol::new(&env, "routine");
+
+    let result = client.try_schedule_care_plan_review(
+        &plan_id,
+        &outsider,
+        &3_600_000u64,
+        &routine,
+        &ReviewKind::Scheduled,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &3_600_000u64,
+        &routine,
+        &ReviewKind::Scheduled,
+    );
+    let hash = BytesN::from_array(&env, &[1u8; 32]);
+    let mods = Vec::new(&env);
+    let result =
+        client.try_conduct_care_plan_review(&review_id, &outsider, &hash, &mods, &false, &false);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert!(!client.get_review(&review_id).conducted);
+}
+
+#[test]
+fn test_conduct_review_updates_plan_dates() {
     let (env, provider, patient) = setup();
+    env.ledger().set_timestamp(5_000_000);
+
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
 
@@ -613,61 +2982,602 @@ fn test_resolve_barrier_twice_fails() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
-    let barrier_id = client
-        .add_barrier(
+    let review_id = client
+        .schedule_care_plan_review(
             &plan_id,
-            &patient,
-            &Symbol::new(&env, "financial"),
-            &String::from_str(&env, "Cannot afford medication"),
-            &1_050_000u64,
+            &provider,
+            &5_000_000u64,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
         )
         ;
 
+    let hash = BytesN::from_array(&env, &[2u8; 32]);
+    let mods = Vec::new(&env);
+
     client
-        .resolve_barrier(
-            &barrier_id,
+        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true, &false)
+        ;
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.last_review_date, Some(5_000_000));
+    // next = 5_000_000 + 30 * 86_400
+    assert_eq!(summary.next_review_date, 5_000_000 + 30 * 86_400);
+}
+
+#[test]
+fn test_interim_review_keeps_next_review_date() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let original_next = 1_000_000 + 30 * 86_400;
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &1_500_000u64,
+        &Symbol::new(&env, "check_in"),
+        &ReviewKind::Interim,
+    );
+
+    env.ledger().set_timestamp(1_500_000);
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[3u8; 32]),
+        &Vec::new(&env),
+        &true,
+        &false,
+    );
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.last_review_date, Some(1_500_000));
+    assert_eq!(summary.next_review_date, original_next);
+}
+
+#[test]
+fn test_conduct_review_schedules_follow_up() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000_000u64,
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+
+    env.ledger().set_timestamp(2_000_000);
+    let follow_up = client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[5u8; 32]),
+        &Vec::new(&env),
+        &true,
+        &true,
+    );
+    assert_eq!(follow_up, Some(review_id + 1));
+
+    // The follow-up is a real, unconducted review
+    let result = client.conduct_care_plan_review(
+        &follow_up.unwrap(),
+        &provider,
+        &BytesN::from_array(&env, &[6u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &true,
+    );
+    assert_eq!(result, None);
+}
+
+#[test]
+fn test_reschedule_review() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000_000u64,
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+
+    env.ledger().set_timestamp(1_500_000);
+    assert_eq!(
+        client.try_reschedule_review(&review_id, &provider, &1_400_000u64),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    client.reschedule_review(&review_id, &provider, &2_500_000u64);
+    assert_eq!(client.get_review(&review_id).review_date, 2_500_000);
+
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &Vec::new(&env),
+        &true,
+        &false,
+    );
+    assert_eq!(
+        client.try_reschedule_review(&review_id, &provider, &3_000_000u64),
+        Err(Ok(Error::ReviewAlreadyConducted))
+    );
+}
+
+#[test]
+fn test_cancel_review_excluded_from_overdue() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let schedule = |date: u64| {
+        client.schedule_care_plan_review(
+            &plan_id,
             &provider,
-            &String::from_str(&env, "Resolved"),
-            &1_100_000u64,
+            &date,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
         )
-        ;
+    };
+    let missed = schedule(1_200_000);
+    let cancelled = schedule(1_300_000);
 
-    let result = client.try_resolve_barrier(
-        &barrier_id,
+    let reason = String::from_str(&env, "Plan changed");
+    client.cancel_review(&cancelled, &provider, &reason);
+    let review = client.get_review(&cancelled);
+    assert!(review.cancelled);
+    assert_eq!(review.cancel_reason, Some(reason.clone()));
+
+    env.ledger().set_timestamp(2_000_000);
+    assert_eq!(client.list_overdue_reviews(&plan_id), vec![&env, missed]);
+
+    assert_eq!(
+        client.try_cancel_review(&cancelled, &provider, &reason),
+        Err(Ok(Error::ReviewCancelled))
+    );
+    assert_eq!(
+        client.try_conduct_care_plan_review(
+            &cancelled,
+            &provider,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &Vec::new(&env),
+            &true,
+            &false,
+        ),
+        Err(Ok(Error::ReviewCancelled))
+    );
+}
+
+#[test]
+fn test_list_plan_reviews() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    assert_eq!(client.list_plan_reviews(&plan_id, &0, &0).len(), 0);
+
+    let schedule = |date: u64| {
+        client.schedule_care_plan_review(
+            &plan_id,
+            &provider,
+            &date,
+            &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
+        )
+    };
+    let first = schedule(1_200_000);
+    let second = schedule(1_300_000);
+    let third = schedule(1_400_000);
+
+    let all = client.list_plan_reviews(&plan_id, &0, &0);
+    assert_eq!(all.len(), 3);
+    assert_eq!(all.get(0).unwrap().review_id, first);
+    assert_eq!(all.get(2).unwrap().review_id, third);
+
+    let page = client.list_plan_reviews(&plan_id, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap(), client.get_review(&second));
+    assert_eq!(client.try_get_review(&99), Err(Ok(Error::ReviewNotFound)));
+}
+
+#[test]
+fn test_flag_overdue_review() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let ids = vec![&env, plan_id];
+    let load = || client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+    let due = load().next_review_date;
+
+    env.ledger().set_timestamp(due);
+    assert!(!client.is_review_overdue(&plan_id));
+    assert!(!client.flag_overdue_review(&plan_id));
+
+    env.ledger().set_timestamp(due + 1);
+    assert!(client.is_review_overdue(&plan_id));
+    assert!(client.flag_overdue_review(&plan_id));
+    assert_eq!(load().status, CarePlanStatus::UnderReview);
+    // Repeated keeper calls are no-ops
+    assert!(!client.flag_overdue_review(&plan_id));
+    assert!(!client.is_review_overdue(&plan_id));
+    assert_eq!(client.get_status_history(&plan_id).len(), 2);
+
+    // Conducting a review that continues the plan reactivates it
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &(due + 1),
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &Vec::new(&env),
+        &true,
+        &false,
+    );
+    assert_eq!(load().status, CarePlanStatus::Active);
+    assert!(!client.is_review_overdue(&plan_id));
+}
+
+#[test]
+fn test_review_grace_period() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let other_plan = create_plan(&env, &client, &patient, &provider);
+    let ids = vec![&env, plan_id];
+    let due = client
+        .get_plans_batch(&ids)
+        .get(0)
+        .unwrap()
+        .unwrap()
+        .next_review_date;
+
+    let result = client.try_set_review_grace_days(&provider, &7);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    client.set_review_grace_days(&admin, &7);
+    assert_eq!(client.get_review_grace_days(&plan_id), 7);
+
+    // Within the grace window the review is late but not yet overdue
+    env.ledger().set_timestamp(due + 7 * 86_400);
+    assert!(!client.is_review_overdue(&plan_id));
+    assert!(!client.flag_overdue_review(&plan_id));
+    env.ledger().set_timestamp(due + 7 * 86_400 + 1);
+    assert!(client.is_review_overdue(&plan_id));
+
+    // A per-plan override takes precedence, and only the plan's provider may set it
+    let outsider = Address::generate(&env);
+    let result = client.try_set_plan_review_grace(&plan_id, &outsider, &14);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    client.set_plan_review_grace(&plan_id, &provider, &14);
+    assert_eq!(client.get_review_grace_days(&plan_id), 14);
+    assert!(!client.is_review_overdue(&plan_id));
+    assert!(client.flag_overdue_review(&other_plan));
+}
+
+#[test]
+fn test_status_history_tracks_completion() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    env.ledger().set_timestamp(1_000);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let history = client.get_status_history(&plan_id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap(), (CarePlanStatus::Active, 1_000));
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000u64,
+        &Symbol::new(&env, "final"),
+        &ReviewKind::Scheduled,
+    );
+
+    env.ledger().set_timestamp(2_000);
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
+
+    let history = client.get_status_history(&plan_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(1).unwrap(), (CarePlanStatus::Completed, 2_000));
+}
+
+#[test]
+fn test_cosign_activates_pending_plan() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    client.set_cosign_required(&admin, &true);
+
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let ids = vec![&env, plan_id];
+    let load = || client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+    assert_eq!(load().status, CarePlanStatus::PendingApproval);
+    assert_eq!(client.get_plan_cosignature(&plan_id), None);
+
+    // The author cannot co-sign their own plan
+    let result = client.try_cosign_care_plan(&plan_id, &provider);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Nor can the patient or a provider outside the care team
+    let result = client.try_cosign_care_plan(&plan_id, &patient);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let second = Address::generate(&env);
+    let result = client.try_cosign_care_plan(&plan_id, &second);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    env.ledger().set_timestamp(5_000);
+    let physician = Symbol::new(&env, "physician");
+    client.assign_care_team_member(&plan_id, &provider, &second, &physician, &Vec::new(&env));
+    client.cosign_care_plan(&plan_id, &second);
+    assert_eq!(load().status, CarePlanStatus::Active);
+    let cosignature = client.get_plan_cosignature(&plan_id).unwrap();
+    assert_eq!(cosignature.cosigner, second);
+    assert_eq!(cosignature.cosigned_at, 5_000);
+    let activated = client.get_status_history(&plan_id).get(1).unwrap();
+    assert_eq!(activated, (CarePlanStatus::Active, 5_000));
+
+    let result = client.try_cosign_care_plan(&plan_id, &second);
+    assert_eq!(result, Err(Ok(Error::PlanNotPending)));
+}
+
+#[test]
+fn test_cosign_rechecks_single_active_plan() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &true);
+    client.set_cosign_required(&admin, &true);
+
+    let first = create_plan(&env, &client, &patient, &provider);
+    let ids = vec![&env, first];
+    let mut second = client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+
+    // A second waiting plan of the same type, as stored before waiting plans
+    // held the slot
+    second.care_plan_id = 99;
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::CarePlan(99), &second);
+        let mut plans: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PatientPlans(patient.clone()))
+            .unwrap();
+        plans.push_back(99);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PatientPlans(patient.clone()), &plans);
+    });
+
+    let cosigner = Address::generate(&env);
+    let physician = Symbol::new(&env, "physician");
+    for plan_id in [first, 99] {
+        client.assign_care_team_member(&plan_id, &provider, &cosigner, &physician, &Vec::new(&env));
+    }
+
+    // Only one of the waiting plans may become the patient's Active plan
+    client.cosign_care_plan(&first, &cosigner);
+    assert_eq!(
+        client.try_cosign_care_plan(&99, &cosigner),
+        Err(Ok(Error::DuplicateActivePlan))
+    );
+}
+
+#[test]
+fn test_open_plans_hold_single_active_slot() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &true);
+    client.set_cosign_required(&admin, &true);
+
+    let create = |patient: &Address| {
+        client.try_create_care_plan(
+            patient,
+            &provider,
+            &Symbol::new(&env, "chronic_disease"),
+            &vec![&env, String::from_str(&env, "Diabetes")],
+            &Vec::new(&env),
+            &1_000_000u64,
+            &30u32,
+            &None,
+            &None,
+        )
+    };
+
+    // A plan awaiting co-signature holds the slot
+    assert!(create(&patient).is_ok());
+    assert_eq!(create(&patient), Err(Ok(Error::DuplicateActivePlan)));
+
+    // So does a plan under review
+    client.set_cosign_required(&admin, &false);
+    let other = Address::generate(&env);
+    let plan_id = create(&other).unwrap().unwrap();
+    env.ledger().set_timestamp(1_000_000 + 31 * 86_400);
+    client.flag_overdue_review(&plan_id);
+    let ids = vec![&env, plan_id];
+    let status = client.get_plans_batch(&ids).get(0).unwrap().unwrap().status;
+    assert_eq!(status, CarePlanStatus::UnderReview);
+    assert_eq!(create(&other), Err(Ok(Error::DuplicateActivePlan)));
+}
+
+#[test]
+fn test_time_boxed_plan_expires() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let conditions = vec![&env, String::from_str(&env, "Knee replacement")];
+    let post_op = Symbol::new(&env, "post_op");
+    let start = 1_000_000u64;
+    let end = start + 90 * 86_400;
+    let plan_id = client.create_care_plan(
+        &patient,
         &provider,
-        &String::from_str(&env, "Again"),
-        &1_200_000u64,
+        &post_op,
+        &conditions,
+        &Vec::new(&env),
+        &start,
+        &30u32,
+        &Some(end),
+        &None,
     );
-
-    assert!(result.is_err());
+    let ids = vec![&env, plan_id];
+    let load = || client.get_plans_batch(&ids).get(0).unwrap().unwrap();
+    assert_eq!(load().end_date, Some(end));
+
+    env.ledger().set_timestamp(end);
+    assert!(!client.check_plan_expiry(&plan_id));
+
+    env.ledger().set_timestamp(end + 1);
+    assert!(client.check_plan_expiry(&plan_id));
+    assert_eq!(load().status, CarePlanStatus::Completed);
+    let closed = client.get_status_history(&plan_id).get(1).unwrap();
+    assert_eq!(closed, (CarePlanStatus::Completed, end + 1));
+    // Already closed
+    assert!(!client.check_plan_expiry(&plan_id));
+
+    // Open-ended plans never expire, and the end must follow the start
+    let open_ended = create_plan(&env, &client, &patient, &provider);
+    assert!(!client.check_plan_expiry(&open_ended));
+    let result = client.try_create_care_plan(
+        &patient,
+        &provider,
+        &post_op,
+        &conditions,
+        &Vec::new(&env),
+        &start,
+        &30u32,
+        &Some(start),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
 }
 
 #[test]
-fn test_add_barrier_plan_not_found() {
-    let (env, _, patient) = setup();
+fn test_generate_completion_report() {
+    let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let result = client.try_add_barrier(
-        &999,
-        &patient,
-        &Symbol::new(&env, "social"),
-        &String::from_str(&env, "No transport"),
+    let mut goal_ids = Vec::new(&env);
+    for _ in 0..3 {
+        goal_ids.push_back(client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Goal"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "medium"),
+            &None,
+        ));
+    }
+    client.mark_goal_achieved(
+        &goal_ids.get(0).unwrap(),
+        &provider,
+        &1_500_000u64,
+        &String::from_str(&env, "Met"),
+    );
+    let barrier_id = client.add_barrier(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "transport"),
+        &String::from_str(&env, "No ride"),
+        &1_000_000u64,
+    );
+    client.resolve_barrier(
+        &barrier_id,
+        &provider,
+        &String::from_str(&env, "Arranged shuttle"),
+        &1_100_000u64,
+    );
+    client.add_barrier(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "financial"),
+        &String::from_str(&env, "Copay"),
         &1_000_000u64,
     );
 
-    assert!(result.is_err());
+    assert_eq!(
+        client.try_generate_completion_report(&plan_id),
+        Err(Ok(Error::PlanNotCompleted))
+    );
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000_000u64,
+        &Symbol::new(&env, "final"),
+        &ReviewKind::Scheduled,
+    );
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
+
+    env.ledger().set_timestamp(3_000);
+    let report = client.generate_completion_report(&plan_id);
+    assert_eq!(report.final_status, CarePlanStatus::Completed);
+    assert_eq!(report.goals_achieved, 1);
+    assert_eq!(report.goals_open, 2);
+    assert_eq!(report.barriers_resolved, 1);
+    assert_eq!(report.barriers_open, 1);
+    assert_eq!(report.reviews_conducted, 1);
+    assert_eq!(report.generated_at, 3_000);
+
+    // Regenerating returns the stored report unchanged
+    env.ledger().set_timestamp(4_000);
+    assert_eq!(client.generate_completion_report(&plan_id), report);
 }
 
 // -----------------------------------------------------------------------
-// schedule_care_plan_review / conduct_care_plan_review
+// assign_care_team_member
 // -----------------------------------------------------------------------
 
 #[test]
-fn test_schedule_review_success() {
+fn test_assign_care_team_member_success() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
@@ -686,23 +3596,32 @@ fn test_schedule_review_success() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
-    let review_id = client
-        .schedule_care_plan_review(
+    let specialist = Address::generate(&env);
+    let mut responsibilities = Vec::new(&env);
+    responsibilities.push_back(String::from_str(&env, "Monitor blood sugar"));
+
+    client
+        .assign_care_team_member(
             &plan_id,
             &provider,
-            &3_600_000u64,
-            &Symbol::new(&env, "routine"),
+            &specialist,
+            &Symbol::new(&env, "specialist"),
+            &responsibilities,
         )
         ;
 
-    assert_eq!(review_id, 1);
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.care_team.len(), 1);
+    assert_eq!(summary.care_team.get(0).unwrap().team_member, specialist);
 }
 
 #[test]
-fn test_conduct_review_success() {
+fn test_assign_multiple_team_members() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
@@ -721,218 +3640,435 @@ fn test_conduct_review_success() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
-    let review_id = client
-        .schedule_care_plan_review(
+    let nurse = Address::generate(&env);
+    let dietitian = Address::generate(&env);
+
+    let mut r1 = Vec::new(&env);
+    r1.push_back(String::from_str(&env, "Wound care"));
+    let mut r2 = Vec::new(&env);
+    r2.push_back(String::from_str(&env, "Nutrition plan"));
+
+    client
+        .assign_care_team_member(
             &plan_id,
             &provider,
-            &3_600_000u64,
-            &Symbol::new(&env, "routine"),
+            &nurse,
+            &Symbol::new(&env, "nurse"),
+            &r1,
         )
         ;
 
-    let hash = BytesN::from_array(&env, &[1u8; 32]);
-    let mut mods = Vec::new(&env);
-    mods.push_back(String::from_str(&env, "Increase exercise frequency"));
-
     client
-        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true)
+        .assign_care_team_member(
+            &plan_id,
+            &provider,
+            &dietitian,
+            &Symbol::new(&env, "dietitian"),
+            &r2,
+        )
         ;
+
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(summary.care_team.len(), 2);
 }
 
 #[test]
-fn test_conduct_review_twice_fails() {
+fn test_role_permission_matrix() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
+    let nurse = Address::generate(&env);
+    let physician = Address::generate(&env);
+    let nurse_role = Symbol::new(&env, "nurse");
+    let physician_role = Symbol::new(&env, "physician");
+    client.assign_care_team_member(&plan_id, &provider, &nurse, &nurse_role, &Vec::new(&env));
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &physician,
+        &physician_role,
+        &Vec::new(&env),
+    );
 
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
+    let nurse_perms = vec![&env, Symbol::new(&env, "add_intervention")];
+    let physician_perms = vec![&env, Symbol::new(&env, "achieve_goal")];
+    client.set_role_permissions(&plan_id, &provider, &nurse_role, &nurse_perms);
+    client.set_role_permissions(&plan_id, &provider, &physician_role, &physician_perms);
+    assert_eq!(client.get_role_permissions(&plan_id).len(), 2);
 
-    let review_id = client
-        .schedule_care_plan_review(
+    let intervention = |by: &Address| {
+        client.try_add_intervention(
             &plan_id,
-            &provider,
-            &3_600_000u64,
-            &Symbol::new(&env, "routine"),
+            by,
+            &Symbol::new(&env, "education"),
+            &String::from_str(&env, "Foot care"),
+            &String::from_str(&env, "Weekly"),
+            &Symbol::new(&env, "provider"),
+            &None,
+            &None,
         )
-        ;
+    };
+    assert!(intervention(&nurse).is_ok());
+    assert_eq!(intervention(&physician), Err(Ok(Error::Unauthorized)));
+    assert!(intervention(&provider).is_ok());
 
-    let hash = BytesN::from_array(&env, &[1u8; 32]);
-    let mods = Vec::new(&env);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "HbA1c below 7%"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    let notes = String::from_str(&env, "Target reached");
+    assert_eq!(
+        client.try_mark_goal_achieved(&goal_id, &nurse, &1_500_000u64, &notes),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.mark_goal_achieved(&goal_id, &physician, &1_500_000u64, &notes);
 
-    client
-        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true)
-        ;
+    // Only the coordinating provider may edit the matrix
+    assert_eq!(
+        client.try_set_role_permissions(&plan_id, &nurse, &nurse_role, &Vec::new(&env)),
+        Err(Ok(Error::Unauthorized))
+    );
+}
 
-    let result =
-        client.try_conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true);
+#[test]
+fn test_team_assignment_gated_by_role_matrix() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let coordinator_role = Symbol::new(&env, "coordinator");
+    let physician_role = Symbol::new(&env, "physician");
+    let nurse_role = Symbol::new(&env, "nurse");
+    let manage = vec![&env, Symbol::new(&env, "manage_team")];
+    let achieve = vec![&env, Symbol::new(&env, "achieve_goal")];
+    client.set_role_permissions(&plan_id, &provider, &coordinator_role, &manage);
+    client.set_role_permissions(&plan_id, &provider, &physician_role, &achieve);
+
+    // An outsider cannot give itself a privileged role
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_assign_care_team_member(
+            &plan_id,
+            &outsider,
+            &outsider,
+            &physician_role,
+            &Vec::new(&env),
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
 
-    assert!(result.is_err());
+    // Nor can a member whose role lacks manage_team
+    let nurse = Address::generate(&env);
+    client.assign_care_team_member(&plan_id, &provider, &nurse, &nurse_role, &Vec::new(&env));
+    assert_eq!(
+        client.try_assign_care_team_member(
+            &plan_id,
+            &nurse,
+            &nurse,
+            &physician_role,
+            &Vec::new(&env),
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // A delegated coordinator can staff the plan but not hand on manage_team
+    let coordinator = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &coordinator,
+        &coordinator_role,
+        &Vec::new(&env),
+    );
+    let physician = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &coordinator,
+        &physician,
+        &physician_role,
+        &Vec::new(&env),
+    );
+    assert_eq!(
+        client.get_team_member_role(&plan_id, &physician),
+        Some(physician_role)
+    );
+    assert_eq!(
+        client.try_assign_care_team_member(
+            &plan_id,
+            &coordinator,
+            &outsider,
+            &coordinator_role,
+            &Vec::new(&env),
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
 }
 
 #[test]
-fn test_conduct_review_updates_plan_dates() {
+fn test_plan_notes_log() {
     let (env, provider, patient) = setup();
-    env.ledger().set_timestamp(5_000_000);
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let nurse = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &nurse,
+        &Symbol::new(&env, "nurse"),
+        &Vec::new(&env),
+    );
+
+    let first = BytesN::from_array(&env, &[1u8; 32]);
+    let second = BytesN::from_array(&env, &[2u8; 32]);
+    let index = client.add_plan_note(&plan_id, &provider, &first, &1_000u64);
+    assert_eq!(index, 0);
+    let index = client.add_plan_note(&plan_id, &nurse, &second, &2_000u64);
+    assert_eq!(index, 1);
+
+    let notes = client.get_plan_notes(&plan_id);
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes.get(0).unwrap().note_hash, first);
+    assert_eq!(notes.get(1).unwrap().author, nurse);
+    assert_eq!(notes.get(1).unwrap().timestamp, 2_000);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_add_plan_note(&plan_id, &outsider, &first, &3_000u64),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_plan_note(&99, &provider, &first, &3_000u64),
+        Err(Ok(Error::CarePlanNotFound))
+    );
+}
 
+#[test]
+fn test_goal_comment_thread() {
+    let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Walk 5000 steps"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "medium"),
+        &None,
+    );
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
+    let nurse = Address::generate(&env);
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &nurse,
+        &Symbol::new(&env, "nurse"),
+        &Vec::new(&env),
+    );
 
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
+    let stalled = String::from_str(&env, "Knee pain since last week");
+    let index = client.add_goal_comment(&goal_id, &nurse, &stalled, &1_000u64);
+    assert_eq!(index, 0);
+    let reply = String::from_str(&env, "Referred to physio");
+    let index = client.add_goal_comment(&goal_id, &provider, &reply, &2_000u64);
+    assert_eq!(index, 1);
+
+    let thread = client.get_goal_comments(&goal_id);
+    assert_eq!(thread.len(), 2);
+    assert_eq!(thread.get(0).unwrap().author, nurse);
+    assert_eq!(thread.get(0).unwrap().comment, stalled);
+    assert_eq!(thread.get(1).unwrap().timestamp, 2_000);
+    // Comments are separate from formal progress entries
+    assert_eq!(client.get_goal_progress(&goal_id).len(), 0);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_add_goal_comment(&goal_id, &outsider, &reply, &3_000u64),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_goal_comment(&goal_id, &provider, &String::from_str(&env, ""), &3_000u64),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_add_goal_comment(&99, &provider, &reply, &3_000u64),
+        Err(Ok(Error::GoalNotFound))
+    );
+}
 
-    let review_id = client
-        .schedule_care_plan_review(
-            &plan_id,
-            &provider,
-            &5_000_000u64,
-            &Symbol::new(&env, "routine"),
-        )
-        ;
+#[test]
+fn test_set_emergency_contact() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
 
-    let hash = BytesN::from_array(&env, &[2u8; 32]);
-    let mods = Vec::new(&env);
+    assert_eq!(client.get_emergency_contact(&plan_id), None);
 
-    client
-        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true)
-        ;
+    let daughter = Address::generate(&env);
+    let details = BytesN::from_array(&env, &[4u8; 32]);
+    client.set_emergency_contact(
+        &plan_id,
+        &provider,
+        &daughter,
+        &Symbol::new(&env, "daughter"),
+        &details,
+    );
 
-    let summary = client.get_care_plan_summary(&plan_id, &provider);
-    assert_eq!(summary.last_review_date, Some(5_000_000));
-    // next = 5_000_000 + 30 * 86_400
-    assert_eq!(summary.next_review_date, 5_000_000 + 30 * 86_400);
+    let record = client.get_emergency_contact(&plan_id).unwrap();
+    assert_eq!(record.contact, daughter);
+    assert_eq!(record.relationship, Symbol::new(&env, "daughter"));
+    assert_eq!(record.reachable_via_hash, details);
+
+    let result = client.try_set_emergency_contact(
+        &plan_id,
+        &Address::generate(&env),
+        &daughter,
+        &Symbol::new(&env, "daughter"),
+        &details,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
 }
 
 // -----------------------------------------------------------------------
-// assign_care_team_member
+// archive_care_plan
 // -----------------------------------------------------------------------
 
 #[test]
-fn test_assign_care_team_member_success() {
+fn test_archive_completed_plan() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let goal_id = client.add_care_goal(
+        &plan_id,
+        &provider,
+        &String::from_str(&env, "Reduce HbA1c"),
+        &None,
+        &2_000_000u64,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    client.add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "medication"),
+        &String::from_str(&env, "Metformin"),
+        &String::from_str(&env, "Daily"),
+        &Symbol::new(&env, "patient"),
+        &None,
+        &None,
+    );
 
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
+    assert_eq!(
+        client.try_archive_care_plan(&plan_id, &admin),
+        Err(Ok(Error::PlanNotArchivable))
+    );
+
+    let review_id = client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000_000u64,
+        &Symbol::new(&env, "final"),
+        &ReviewKind::Scheduled,
+    );
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
 
-    let specialist = Address::generate(&env);
-    let mut responsibilities = Vec::new(&env);
-    responsibilities.push_back(String::from_str(&env, "Monitor blood sugar"));
+    client.archive_care_plan(&plan_id, &admin);
 
-    client
-        .assign_care_team_member(
-            &plan_id,
-            &provider,
-            &specialist,
-            &Symbol::new(&env, "specialist"),
-            &responsibilities,
-        )
-        ;
+    let archive = client.get_archived_plan(&plan_id).unwrap();
+    assert_eq!(archive.plan.care_plan_id, plan_id);
+    assert_eq!(archive.goals.len(), 1);
+    assert_eq!(archive.goals.get(0).unwrap().goal_id, goal_id);
+    assert_eq!(archive.interventions.len(), 1);
 
-    let summary = client.get_care_plan_summary(&plan_id, &provider);
-    assert_eq!(summary.care_team.len(), 1);
-    assert_eq!(summary.care_team.get(0).unwrap().team_member, specialist);
+    assert!(client.try_get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0).is_err());
 }
 
 #[test]
-fn test_assign_multiple_team_members() {
+fn test_archive_requires_admin() {
     let (env, provider, patient) = setup();
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &false);
 
-    let mut conditions = Vec::new(&env);
-    conditions.push_back(String::from_str(&env, "Diabetes"));
-    let mut goals = Vec::new(&env);
-    goals.push_back(String::from_str(&env, "Goal"));
-
-    let plan_id = client
-        .create_care_plan(
-            &patient,
-            &provider,
-            &Symbol::new(&env, "chronic_disease"),
-            &conditions,
-            &goals,
-            &1_000_000u64,
-            &30u32,
-        )
-        ;
-
-    let nurse = Address::generate(&env);
-    let dietitian = Address::generate(&env);
-
-    let mut r1 = Vec::new(&env);
-    r1.push_back(String::from_str(&env, "Wound care"));
-    let mut r2 = Vec::new(&env);
-    r2.push_back(String::from_str(&env, "Nutrition plan"));
-
-    client
-        .assign_care_team_member(
-            &plan_id,
-            &provider,
-            &nurse,
-            &Symbol::new(&env, "nurse"),
-            &r1,
-        )
-        ;
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    assert_eq!(
+        client.try_archive_care_plan(&plan_id, &provider),
+        Err(Ok(Error::Unauthorized))
+    );
+}
 
-    client
-        .assign_care_team_member(
-            &plan_id,
-            &provider,
-            &dietitian,
-            &Symbol::new(&env, "dietitian"),
-            &r2,
-        )
-        ;
+#[test]
+fn test_auto_archive_after_retention() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
 
-    let summary = client.get_care_plan_summary(&plan_id, &provider);
-    assert_eq!(summary.care_team.len(), 2);
+    let start = 1_000_000u64;
+    let end = start + 86_400;
+    let plan_id = client.create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "post_op"),
+        &vec![&env, String::from_str(&env, "Hip replacement")],
+        &Vec::new(&env),
+        &start,
+        &30u32,
+        &Some(end),
+        &None,
+    );
+    let completed_at = end + 1;
+    env.ledger().set_timestamp(completed_at);
+    client.check_plan_expiry(&plan_id);
+
+    // No policy configured yet
+    env.ledger().set_timestamp(completed_at + 365 * 86_400);
+    assert!(!client.auto_archive_eligible(&plan_id));
+
+    client.set_archive_policy(&admin, &30u32);
+    env.ledger().set_timestamp(completed_at + 30 * 86_400);
+    let result = client.try_set_archive_policy(&provider, &1u32);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert!(!client.auto_archive_eligible(&plan_id));
+
+    env.ledger().set_timestamp(completed_at + 30 * 86_400 + 1);
+    assert!(client.auto_archive_eligible(&plan_id));
+    let archive = client.get_archived_plan(&plan_id).unwrap();
+    assert_eq!(archive.archived_by, contract_id);
+    assert!(!client.auto_archive_eligible(&plan_id));
+
+    // Active plans are never auto-archived
+    let active = create_plan(&env, &client, &patient, &provider);
+    assert!(!client.auto_archive_eligible(&active));
 }
 
 // -----------------------------------------------------------------------
@@ -945,7 +4081,7 @@ fn test_get_care_plan_summary_not_found() {
     let contract_id = env.register(CarePlanContract, ());
     let client = CarePlanContractClient::new(&env, &contract_id);
 
-    let result = client.try_get_care_plan_summary(&999, &provider);
+    let result = client.try_get_care_plan_summary(&999, &provider, &0, &0, &0, &0, &0, &0);
     assert!(result.is_err());
 }
 
@@ -969,6 +4105,8 @@ fn test_get_care_plan_summary_excludes_achieved_goals() {
             &goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -980,6 +4118,7 @@ fn test_get_care_plan_summary_excludes_achieved_goals() {
             &None,
             &2_000_000u64,
             &Symbol::new(&env, "high"),
+            &None,
         )
         ;
 
@@ -991,6 +4130,7 @@ fn test_get_care_plan_summary_excludes_achieved_goals() {
             &None,
             &2_000_000u64,
             &Symbol::new(&env, "low"),
+            &None,
         )
         ;
 
@@ -1003,11 +4143,316 @@ fn test_get_care_plan_summary_excludes_achieved_goals() {
         )
         ;
 
-    let summary = client.get_care_plan_summary(&plan_id, &provider);
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
     assert_eq!(summary.active_goals.len(), 1);
     assert_eq!(summary.active_goals.get(0).unwrap().goal_id, goal_id);
 }
 
+#[test]
+fn test_get_care_plan_summary_pages_lists() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    for _ in 0..5 {
+        client.add_intervention(
+            &plan_id,
+            &provider,
+            &Symbol::new(&env, "exercise"),
+            &String::from_str(&env, "Walk"),
+            &String::from_str(&env, "Daily"),
+            &Symbol::new(&env, "patient"),
+            &None,
+            &None,
+        );
+    }
+    for _ in 0..3 {
+        client.add_barrier(
+            &plan_id,
+            &provider,
+            &Symbol::new(&env, "financial"),
+            &String::from_str(&env, "Cost"),
+            &1_000_000u64,
+        );
+    }
+
+    let page = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &2, &0, &2);
+    assert_eq!(page.interventions.len(), 2);
+    assert_eq!(page.next_intervention_offset, Some(2));
+    assert_eq!(page.barriers.len(), 2);
+    assert_eq!(page.next_barrier_offset, Some(2));
+    assert_eq!(page.next_goal_offset, None);
+
+    let last = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &4, &2, &2, &2);
+    assert_eq!(last.interventions.len(), 1);
+    assert_eq!(last.interventions.get(0).unwrap().intervention_id, 5);
+    assert_eq!(last.next_intervention_offset, None);
+    assert_eq!(last.barriers.len(), 1);
+    assert_eq!(last.next_barrier_offset, None);
+
+    // Zero limits return everything in one call
+    let full = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
+    assert_eq!(full.interventions.len(), 5);
+    assert_eq!(full.barriers.len(), 3);
+}
+
+#[test]
+fn test_get_patient_overview_spans_plans() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let first = create_plan(&env, &client, &patient, &provider);
+    let second = create_plan(&env, &client, &patient, &provider);
+    create_plan(&env, &client, &Address::generate(&env), &provider);
+
+    for plan_id in [first, second] {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, "Goal"),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "medium"),
+            &None,
+        );
+        client.add_barrier(
+            &plan_id,
+            &provider,
+            &Symbol::new(&env, "adherence"),
+            &String::from_str(&env, "Missed doses"),
+            &1_000_000u64,
+        );
+    }
+
+    let review_id = client.schedule_care_plan_review(
+        &second,
+        &provider,
+        &1_500_000u64,
+        &Symbol::new(&env, "final"),
+        &ReviewKind::Scheduled,
+    );
+    client.conduct_care_plan_review(
+        &review_id,
+        &provider,
+        &BytesN::from_array(&env, &[0u8; 32]),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
+
+    let overview = client.get_patient_overview(&patient, &provider);
+    assert_eq!(overview.total_plans, 2);
+    assert_eq!(overview.active_plans, 1);
+    assert_eq!(overview.active_goals, 2);
+    assert_eq!(overview.open_barriers, 2);
+
+    // Outsiders see nothing, and providers lose sight of unconsented plans
+    let outsider = Address::generate(&env);
+    let overview = client.get_patient_overview(&patient, &outsider);
+    assert_eq!(overview.total_plans, 0);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    client.set_consent_required(&admin, &true);
+    client.record_patient_consent(
+        &first,
+        &patient,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &1_000_000u64,
+    );
+    let overview = client.get_patient_overview(&patient, &provider);
+    assert_eq!(overview.total_plans, 1);
+    assert_eq!(overview.active_goals, 1);
+    let overview = client.get_patient_overview(&patient, &patient);
+    assert_eq!(overview.total_plans, 2);
+}
+
+// -----------------------------------------------------------------------
+// export_snapshot
+// -----------------------------------------------------------------------
+
+#[test]
+fn test_export_snapshot_bundles_plan() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let add_goal = |description: &str| {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, description),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+    };
+    add_goal("Reduce HbA1c");
+    let voided = add_goal("Duplicate");
+    client.void_goal(&voided, &provider, &String::from_str(&env, "Entered twice"));
+    client.add_intervention(
+        &plan_id,
+        &provider,
+        &Symbol::new(&env, "medication"),
+        &String::from_str(&env, "Metformin"),
+        &String::from_str(&env, "Daily"),
+        &Symbol::new(&env, "patient"),
+        &None,
+        &None,
+    );
+    client.add_barrier(
+        &plan_id,
+        &patient,
+        &Symbol::new(&env, "financial"),
+        &String::from_str(&env, "Cost of strips"),
+        &1_050_000u64,
+    );
+    client.schedule_care_plan_review(
+        &plan_id,
+        &provider,
+        &2_000_000u64,
+        &Symbol::new(&env, "routine"),
+        &ReviewKind::Scheduled,
+    );
+    client.assign_care_team_member(
+        &plan_id,
+        &provider,
+        &Address::generate(&env),
+        &Symbol::new(&env, "nurse"),
+        &Vec::new(&env),
+    );
+
+    env.ledger().set_timestamp(1_100_000);
+    let snapshot = client.export_snapshot(&plan_id, &patient);
+    assert_eq!(snapshot.plan.care_plan_id, plan_id);
+    assert_eq!(snapshot.goals.len(), 2);
+    assert!(snapshot.goals.get(1).unwrap().voided);
+    assert_eq!(snapshot.interventions.len(), 1);
+    assert_eq!(snapshot.barriers.len(), 1);
+    assert_eq!(snapshot.reviews.len(), 1);
+    assert_eq!(snapshot.care_team.len(), 1);
+    assert_eq!(snapshot.exported_at, 1_100_000);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_export_snapshot(&plan_id, &outsider),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_export_snapshot(&99, &provider),
+        Err(Ok(Error::CarePlanNotFound))
+    );
+}
+
+#[test]
+fn test_import_snapshot_into_another_deployment() {
+    let (env, provider, patient) = setup();
+    let source_id = env.register(CarePlanContract, ());
+    let source = CarePlanContractClient::new(&env, &source_id);
+    let plan_id = create_plan(&env, &source, &patient, &provider);
+    // Offset the target's counters so ids visibly change on import
+    let target_id = env.register(CarePlanContract, ());
+    let target = CarePlanContractClient::new(&env, &target_id);
+    let admin = Address::generate(&env);
+    target.initialize(&admin, &false);
+    create_plan(&env, &target, &patient, &provider);
+
+    let add_goal = |description: &str| {
+        source.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, description),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "high"),
+            &None,
+        )
+    };
+    let first = add_goal("Start exercise program");
+    let second = add_goal("Walk 5km");
+    source.set_goal_dependencies(&second, &provider, &vec![&env, first]);
+    let value = String::from_str(&env, "2 sessions");
+    let note = String::from_str(&env, "Week one");
+    source.record_goal_progress(&first, &patient, &value, &note, &1_050_000u64);
+    let value = String::from_str(&env, "3 sessions");
+    source.correct_progress_entry(&first, &0, &provider, &value, &note);
+    env.ledger().set_timestamp(1_070_000);
+    let outcome = String::from_str(&env, "Exercising three times a week");
+    source.mark_goal_achieved(&first, &provider, &1_065_000u64, &outcome);
+    let barrier_id = source.add_barrier(
+        &plan_id,
+        &patient,
+        &Symbol::new(&env, "transport"),
+        &String::from_str(&env, "No ride"),
+        &1_050_000u64,
+    );
+    source.resolve_barrier(
+        &barrier_id,
+        &provider,
+        &String::from_str(&env, "Ride program"),
+        &1_060_000u64,
+    );
+
+    let snapshot = source.export_snapshot(&plan_id, &provider);
+    let result = target.try_import_snapshot(&provider, &snapshot);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let imported = target.import_snapshot(&admin, &snapshot);
+    assert_ne!(imported, plan_id);
+
+    let copy = target.export_snapshot(&imported, &provider);
+    assert_eq!(copy.plan.care_plan_id, imported);
+    assert_eq!(copy.plan.patient_id, patient);
+    assert_eq!(copy.goals.len(), 2);
+    let new_first = copy.goals.get(0).unwrap();
+    let new_second = copy.goals.get(1).unwrap();
+    assert_eq!(new_first.care_plan_id, imported);
+    assert_eq!(new_second.depends_on, vec![&env, new_first.goal_id]);
+    // Progress moves with its goal, corrections still pointing at the original
+    assert_eq!(new_first.progress_count, 2);
+    let progress = target.get_goal_progress(&new_first.goal_id);
+    assert!(progress.get(0).unwrap().superseded);
+    let correction = progress.get(1).unwrap();
+    assert_eq!(correction.goal_id, new_first.goal_id);
+    assert_eq!(correction.corrects, Some(0));
+    // Outcome notes and the achievement time survive the move
+    assert_eq!(
+        target.get_goal_outcome(&new_first.goal_id),
+        Some((1_065_000, outcome))
+    );
+    let record = copy.outcomes.get(0).unwrap();
+    assert_eq!(record.goal_id, new_first.goal_id);
+    assert_eq!(record.achieved_at, Some(1_070_000));
+    assert!(copy.barriers.get(0).unwrap().resolved);
+    let transport = Symbol::new(&env, "transport");
+    assert_eq!(
+        target.barrier_stats(&imported).get(transport).unwrap(),
+        (0, 1)
+    );
+    assert_eq!(target.get_provider_plans(&provider, &0, &0).len(), 2);
+    assert_eq!(target.provider_achievement_rate(&provider), (1, 2));
+}
+
+#[test]
+fn test_import_snapshot_respects_single_active_plan() {
+    let (env, provider, patient) = setup();
+    let source_id = env.register(CarePlanContract, ());
+    let source = CarePlanContractClient::new(&env, &source_id);
+    let plan_id = create_plan(&env, &source, &patient, &provider);
+    let snapshot = source.export_snapshot(&plan_id, &provider);
+
+    let target_id = env.register(CarePlanContract, ());
+    let target = CarePlanContractClient::new(&env, &target_id);
+    let admin = Address::generate(&env);
+    target.initialize(&admin, &true);
+    create_plan(&env, &target, &patient, &provider);
+
+    let result = target.try_import_snapshot(&admin, &snapshot);
+    assert_eq!(result, Err(Ok(Error::DuplicateActivePlan)));
+}
+
 // -----------------------------------------------------------------------
 // Full workflow integration test
 // -----------------------------------------------------------------------
@@ -1035,6 +4480,8 @@ fn test_full_care_plan_workflow() {
             &initial_goals,
             &1_000_000u64,
             &30u32,
+            &None,
+            &None,
         )
         ;
 
@@ -1047,6 +4494,7 @@ fn test_full_care_plan_workflow() {
             &Some(String::from_str(&env, "6.9")),
             &3_000_000u64,
             &Symbol::new(&env, "high"),
+            &None,
         )
         ;
 
@@ -1059,6 +4507,8 @@ fn test_full_care_plan_workflow() {
             &String::from_str(&env, "Metformin"),
             &String::from_str(&env, "Twice daily"),
             &Symbol::new(&env, "patient"),
+            &None,
+            &None,
         )
         ;
 
@@ -1116,6 +4566,7 @@ fn test_full_care_plan_workflow() {
             &provider,
             &3_592_000u64,
             &Symbol::new(&env, "routine"),
+            &ReviewKind::Scheduled,
         )
         ;
 
@@ -1125,7 +4576,7 @@ fn test_full_care_plan_workflow() {
     mods.push_back(String::from_str(&env, "Increase Metformin to 1000mg"));
 
     client
-        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true)
+        .conduct_care_plan_review(&review_id, &provider, &hash, &mods, &true, &false)
         ;
 
     // 10. Mark goal achieved
@@ -1139,7 +4590,7 @@ fn test_full_care_plan_workflow() {
         ;
 
     // 11. Verify summary
-    let summary = client.get_care_plan_summary(&plan_id, &provider);
+    let summary = client.get_care_plan_summary(&plan_id, &provider, &0, &0, &0, &0, &0, &0);
     assert_eq!(summary.care_plan_id, plan_id);
     assert_eq!(summary.active_goals.len(), 0); // achieved goal excluded
     assert_eq!(summary.interventions.len(), 1);
@@ -1149,3 +4600,245 @@ fn test_full_care_plan_workflow() {
     assert!(summary.barriers.get(0).unwrap().resolved);
     assert!(summary.last_review_date.is_some());
 }
+
+#[test]
+fn test_create_care_plan_idempotency_key() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let conditions = vec![&env, String::from_str(&env, "Asthma")];
+    let goals = Vec::new(&env);
+    let plan_type = Symbol::new(&env, "chronic_disease");
+    let key = Some(BytesN::from_array(&env, &[9u8; 32]));
+    let create = |key: &Option<BytesN<32>>| {
+        client.create_care_plan(
+            &patient,
+            &provider,
+            &plan_type,
+            &conditions,
+            &goals,
+            &1_000,
+            &30,
+            &None,
+            key,
+        )
+    };
+
+    let first = create(&key);
+    // A retried call with the same key returns the original plan
+    assert_eq!(create(&key), first);
+    assert_eq!(client.get_provider_plans(&provider, &0, &10).len(), 1);
+
+    let other_key = Some(BytesN::from_array(&env, &[10u8; 32]));
+    assert_ne!(create(&other_key), first);
+    assert_ne!(create(&None), create(&None));
+    assert_eq!(client.get_provider_plans(&provider, &0, &10).len(), 4);
+
+    // Keys are scoped to the provider, and can't be replayed for another patient
+    let other_provider = Address::generate(&env);
+    let own_plan = client.create_care_plan(
+        &patient,
+        &other_provider,
+        &plan_type,
+        &conditions,
+        &goals,
+        &1_000,
+        &30,
+        &None,
+        &key,
+    );
+    assert_ne!(own_plan, first);
+    let result = client.try_create_care_plan(
+        &Address::generate(&env),
+        &provider,
+        &plan_type,
+        &conditions,
+        &goals,
+        &1_000,
+        &30,
+        &None,
+        &key,
+    );
+    assert_eq!(result, Err(Ok(Error::IdempotencyKeyConflict)));
+}
+
+#[test]
+fn test_get_team_member_role() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+    let other_plan = create_plan(&env, &client, &patient, &provider);
+
+    let member = Address::generate(&env);
+    let nurse = Symbol::new(&env, "nurse");
+    assert_eq!(client.get_team_member_role(&plan_id, &member), None);
+    client.assign_care_team_member(&plan_id, &provider, &member, &nurse, &Vec::new(&env));
+    assert_eq!(client.get_team_member_role(&plan_id, &member), Some(nurse));
+    assert_eq!(client.get_team_member_role(&other_plan, &member), None);
+
+    // A later assignment replaces the reported role
+    let pharmacist = Symbol::new(&env, "pharmacist");
+    client.assign_care_team_member(&plan_id, &provider, &member, &pharmacist, &Vec::new(&env));
+    assert_eq!(
+        client.get_team_member_role(&plan_id, &member),
+        Some(pharmacist)
+    );
+}
+
+#[test]
+fn test_outsider_cannot_join_care_team() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let outsider = Address::generate(&env);
+    let role = Symbol::new(&env, "physician");
+    let result =
+        client.try_assign_care_team_member(&plan_id, &outsider, &outsider, &role, &Vec::new(&env));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.get_team_member_role(&plan_id, &outsider), None);
+}
+
+#[test]
+fn test_create_care_plan_with_derived_id() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+
+    let conditions = vec![&env, String::from_str(&env, "Asthma")];
+    let goals = Vec::new(&env);
+    let plan_type = Symbol::new(&env, "chronic_disease");
+    let create = |provider: &Address, external_ref: &BytesN<32>| {
+        client.try_create_care_plan_with_id(
+            &patient,
+            provider,
+            &plan_type,
+            &conditions,
+            &goals,
+            &1_000,
+            &30,
+            &None,
+            external_ref,
+        )
+    };
+
+    let external_ref = BytesN::from_array(&env, &[5u8; 32]);
+    let plan_id = create(&provider, &external_ref).unwrap().unwrap();
+    // Derived ids stay clear of the sequential counter
+    assert!(plan_id >= 1 << 63);
+    let plans = client.get_plans_batch(&vec![&env, plan_id]);
+    assert_eq!(plans.get(0).unwrap().unwrap().patient_id, patient);
+    assert_eq!(create_plan(&env, &client, &patient, &provider), 1);
+
+    // The same inputs always derive the same id, so a second create collides
+    assert_eq!(
+        create(&provider, &external_ref),
+        Err(Ok(Error::PlanIdTaken))
+    );
+
+    let other_ref = BytesN::from_array(&env, &[6u8; 32]);
+    let other_provider = Address::generate(&env);
+    let by_ref = create(&provider, &other_ref).unwrap().unwrap();
+    let by_provider = create(&other_provider, &external_ref).unwrap().unwrap();
+    assert_ne!(by_ref, plan_id);
+    assert_ne!(by_provider, plan_id);
+}
+
+#[test]
+fn test_list_goals_by_status() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let plan_id = create_plan(&env, &client, &patient, &provider);
+
+    let add_goal = |description: &str| {
+        client.add_care_goal(
+            &plan_id,
+            &provider,
+            &String::from_str(&env, description),
+            &None,
+            &2_000_000u64,
+            &Symbol::new(&env, "medium"),
+            &None,
+        )
+    };
+    let achieved = add_goal("Quit smoking");
+    let open = add_goal("Walk daily");
+    let voided = add_goal("Entered by mistake");
+    let later = add_goal("Lose 5kg");
+    let notes = String::from_str(&env, "Done");
+    client.mark_goal_achieved(&achieved, &provider, &1_500_000u64, &notes);
+    client.void_goal(&voided, &provider, &String::from_str(&env, "Duplicate"));
+
+    let done = client.list_goals_by_status(&plan_id, &GoalStatus::Achieved, &0, &0);
+    assert_eq!(done.len(), 1);
+    assert_eq!(done.get(0).unwrap().goal_id, achieved);
+
+    // Voided goals are hidden whatever their status
+    let active = client.list_goals_by_status(&plan_id, &GoalStatus::Active, &0, &0);
+    assert_eq!(active.len(), 2);
+    assert_eq!(active.get(0).unwrap().goal_id, open);
+    assert_eq!(active.get(1).unwrap().goal_id, later);
+    let first = client.list_goals_by_status(&plan_id, &GoalStatus::Active, &0, &1);
+    assert_eq!(first.len(), 1);
+
+    let at_risk = client.list_goals_by_status(&plan_id, &GoalStatus::AtRisk, &0, &0);
+    assert!(at_risk.is_empty());
+}
+
+#[test]
+fn test_list_patient_open_barriers() {
+    let (env, provider, patient) = setup();
+    let contract_id = env.register(CarePlanContract, ());
+    let client = CarePlanContractClient::new(&env, &contract_id);
+    let diabetes_plan = create_plan(&env, &client, &patient, &provider);
+    let other_provider = Address::generate(&env);
+    let cardiac_plan = create_plan(&env, &client, &patient, &other_provider);
+
+    let transport = Symbol::new(&env, "transport");
+    let add_barrier = |plan_id: u64, description: &str| {
+        let description = String::from_str(&env, description);
+        client.add_barrier(&plan_id, &patient, &transport, &description, &1_050_000u64)
+    };
+    let first = add_barrier(diabetes_plan, "No ride to clinic");
+    let resolved = add_barrier(diabetes_plan, "Bus pass expired");
+    let second = add_barrier(cardiac_plan, "No ride to rehab");
+    let resolution = String::from_str(&env, "Renewed");
+    client.resolve_barrier(&resolved, &provider, &resolution, &1_060_000u64);
+
+    let all = client.list_patient_open_barriers(&patient, &patient);
+    assert_eq!(all.len(), 2);
+    assert_eq!(all.get(0).unwrap().barrier_id, first);
+    assert_eq!(all.get(1).unwrap().barrier_id, second);
+
+    // Providers only see plans they are part of
+    let mine = client.list_patient_open_barriers(&patient, &other_provider);
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine.get(0).unwrap().barrier_id, second);
+    let stranger = Address::generate(&env);
+    assert!(client
+        .list_patient_open_barriers(&patient, &stranger)
+        .is_empty());
+
+    // Once consent is required, providers lose sight of unconsented plans
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &false);
+    client.set_consent_required(&admin, &true);
+    client.record_patient_consent(
+        &cardiac_plan,
+        &patient,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &1_000_000u64,
+    );
+    assert!(client
+        .list_patient_open_barriers(&patient, &provider)
+        .is_empty());
+    let mine = client.list_patient_open_barriers(&patient, &other_provider);
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine.get(0).unwrap().barrier_id, second);
+    let all = client.list_patient_open_barriers(&patient, &patient);
+    assert_eq!(all.len(), 2);
+}
@@ -20,6 +20,27 @@ pub enum Error {
     GoalDiscontinued = 8,
     BarrierAlreadyResolved = 9,
     ReviewAlreadyConducted = 10,
+    VitalLinkNotSet = 11,
+    VitalReadingNotFound = 12,
+    GuidelineAlreadyAttached = 13,
+    ConsentRequired = 14,
+    AlreadyInitialized = 15,
+    NotInitialized = 16,
+    GoalBlocked = 17,
+    InvalidDependency = 18,
+    InvalidInput = 19,
+    PlanNotArchivable = 20,
+    GoalVoided = 21,
+    DuplicateActivePlan = 22,
+    PlanNotCompleted = 23,
+    ReviewCancelled = 24,
+    PlanNotPending = 25,
+    InterventionInactive = 26,
+    RetractionWindowClosed = 27,
+    GoalLimitExceeded = 28,
+    PlanIdTaken = 29,
+    UntrustedGuidelineContract = 30,
+    IdempotencyKeyConflict = 31,
 }
 
 // -----------------------------------------------------------------------
@@ -54,6 +75,18 @@ pub enum CarePlanStatus {
     Completed,
     /// Care plan has been discontinued.
     Discontinued,
+    /// Care plan is waiting for a second provider's co-signature.
+    PendingApproval,
+}
+
+/// Whether a review is the plan's scheduled review or an interim check-in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReviewKind {
+    /// Regular review; resets the plan's next review date.
+    Scheduled,
+    /// Check-in between scheduled reviews; leaves the next review date alone.
+    Interim,
 }
 
 // -----------------------------------------------------------------------
@@ -66,9 +99,17 @@ pub enum CarePlanStatus {
 pub struct ProgressEntry {
     pub goal_id: u64,
     pub patient_id: Address,
+    /// Who logged the entry: the patient, a care team member, or a linked vitals contract.
+    pub recorded_by: Address,
     pub current_value: String,
     pub progress_note: String,
+    /// Fixed-point reading at the goal's scale, for numeric goals.
+    pub numeric_value: Option<i64>,
     pub recorded_date: u64,
+    /// Set once a later correction replaces this entry; the entry is kept for audit.
+    pub superseded: bool,
+    /// Index of the entry this one corrects, if it is a correction.
+    pub corrects: Option<u32>,
 }
 
 /// A care goal associated with a care plan.
@@ -79,12 +120,27 @@ pub struct CareGoal {
     pub care_plan_id: u64,
     pub description: String,
     pub target_value: Option<String>,
+    /// Fixed-point numeric target; the real value is target_number / 10^scale.
+    pub target_number: Option<i64>,
+    pub scale: u32,
+    pub unit: Symbol,
     pub target_date: u64,
     pub priority: Symbol,
     pub status: GoalStatus,
-    pub progress_entries: Vec<ProgressEntry>,
+    /// Number of progress entries stored under GoalProgress(goal_id, index).
+    pub progress_count: u32,
     pub achievement_date: Option<u64>,
-    pub outcome_notes: Option<String>,
+    /// PatientVitalsContract this goal tracks, if any.
+    pub vital_contract: Option<Address>,
+    /// Metric symbol understood by the vitals contract, e.g. heart_rate.
+    pub vital_metric: Option<Symbol>,
+    /// Goal ids that must be achieved before progress can be recorded.
+    pub depends_on: Vec<u64>,
+    /// Voided goals are kept for audit but hidden from normal reads.
+    pub voided: bool,
+    pub void_reason: Option<String>,
+    /// Clinical guideline the goal was derived from; None for provider judgment.
+    pub derived_from_guideline: Option<String>,
     pub created_by: Address,
     pub created_at: u64,
 }
@@ -100,8 +156,69 @@ pub struct Intervention {
     pub frequency: String,
     /// patient | provider | caregiver
     pub responsible_party: Symbol,
+    /// Specific care team member accountable for the intervention.
+    pub assignee: Option<Address>,
     pub assigned_by: Address,
     pub created_at: u64,
+    /// Cleared when the intervention is deactivated.
+    pub active: bool,
+    /// Goal in the same plan that this intervention works towards.
+    pub goal_id: Option<u64>,
+}
+
+/// Feedback on how well an intervention is working.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterventionRating {
+    pub rater: Address,
+    /// 1 (not effective) to 5 (very effective)
+    pub effectiveness: u32,
+    pub note: String,
+    pub rated_at: u64,
+}
+
+/// Audit record of an achievement that was retracted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AchievementRetraction {
+    pub retracted_by: Address,
+    pub retracted_at: u64,
+    pub achievement_date: u64,
+    pub outcome_notes: String,
+}
+
+/// A comment in a goal's discussion thread.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalComment {
+    pub author: Address,
+    pub comment: String,
+    pub timestamp: u64,
+}
+
+/// A free-form plan note; only its hash is stored, the text stays off-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanNote {
+    pub author: Address,
+    pub note_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+/// A condition identified by a standardized code such as ICD-10.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Condition {
+    pub code: Symbol,
+    pub description: String,
+}
+
+/// Second-provider sign-off that activated a plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanCosignature {
+    pub cosigner: Address,
+    pub cosigned_at: u64,
 }
 
 /// A barrier to care plan progress.
@@ -118,6 +235,10 @@ pub struct Barrier {
     pub resolution: Option<String>,
     pub resolution_date: Option<u64>,
     pub resolved_by: Option<Address>,
+    /// Care team member tasked with resolving the barrier.
+    pub assigned_to: Option<Address>,
+    /// Set when the patient, rather than a provider, reported the barrier.
+    pub reported_by_patient: bool,
 }
 
 /// A scheduled review of a care plan.
@@ -129,12 +250,16 @@ pub struct CareReview {
     pub scheduled_by: Address,
     pub review_date: u64,
     pub review_type: Symbol,
+    pub review_kind: ReviewKind,
     pub conducted: bool,
     pub review_notes_hash: Option<BytesN<32>>,
     pub plan_modifications: Vec<String>,
     pub continue_plan: bool,
     pub conducted_by: Option<Address>,
     pub conducted_at: Option<u64>,
+    /// Cancelled reviews are kept for audit but never conducted.
+    pub cancelled: bool,
+    pub cancel_reason: Option<String>,
 }
 
 /// A care team member assigned to a care plan.
@@ -158,16 +283,102 @@ pub struct CarePlan {
     pub provider_id: Address,
     /// chronic_disease | post_op | preventive | palliative
     pub plan_type: Symbol,
+    /// Free-text conditions, kept for display.
     pub conditions: Vec<String>,
+    /// Standardized (e.g. ICD-10) codes for the plan's conditions.
+    pub coded_conditions: Vec<Condition>,
     pub goals: Vec<String>,
     pub start_date: u64,
     pub review_frequency_days: u32,
     pub status: CarePlanStatus,
     pub next_review_date: u64,
     pub last_review_date: Option<u64>,
+    /// Time-boxed plans are completed automatically once this passes.
+    pub end_date: Option<u64>,
     pub created_at: u64,
 }
 
+/// Emergency contact on record for a care plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyContact {
+    pub care_plan_id: u64,
+    pub contact: Address,
+    pub relationship: Symbol,
+    /// Hash of the off-chain contact details (phone, address).
+    pub reachable_via_hash: BytesN<32>,
+    pub set_by: Address,
+    pub set_at: u64,
+}
+
+/// A patient's documented consent to a care plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanConsent {
+    pub care_plan_id: u64,
+    pub patient_id: Address,
+    pub consent_hash: BytesN<32>,
+    pub consent_date: u64,
+    pub recorded_at: u64,
+    pub revoked_at: Option<u64>,
+}
+
+/// Compact record of a goal kept after its plan is archived.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalArchiveSummary {
+    pub goal_id: u64,
+    pub description: String,
+    pub status: GoalStatus,
+    pub achievement_date: Option<u64>,
+}
+
+/// Compact record of an intervention kept after its plan is archived.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterventionArchiveSummary {
+    pub intervention_id: u64,
+    pub intervention_type: Symbol,
+    pub description: String,
+}
+
+/// Archived form of a completed or discontinued care plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchivedPlan {
+    pub plan: CarePlan,
+    pub goals: Vec<GoalArchiveSummary>,
+    pub interventions: Vec<InterventionArchiveSummary>,
+    pub archived_by: Address,
+    pub archived_at: u64,
+}
+
+/// Outcome tally produced once a care plan is closed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompletionReport {
+    pub care_plan_id: u64,
+    pub final_status: CarePlanStatus,
+    pub goals_achieved: u32,
+    pub goals_discontinued: u32,
+    pub goals_open: u32,
+    pub barriers_resolved: u32,
+    pub barriers_open: u32,
+    pub reviews_conducted: u32,
+    pub generated_at: u64,
+}
+
+/// Cross-plan counts returned by get_patient_overview.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PatientOverview {
+    pub patient_id: Address,
+    pub total_plans: u32,
+    pub active_plans: u32,
+    pub active_goals: u32,
+    pub open_barriers: u32,
+}
+
 /// Summary returned by get_care_plan_summary.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -181,6 +392,137 @@ pub struct CarePlanSummary {
     pub barriers: Vec<Barrier>,
     pub last_review_date: Option<u64>,
     pub next_review_date: u64,
+    /// Offsets to pass back for the next page; None once a list is exhausted.
+    pub next_goal_offset: Option<u32>,
+    pub next_intervention_offset: Option<u32>,
+    pub next_barrier_offset: Option<u32>,
+}
+
+/// Everything recorded for one care plan, for off-chain backup or transfer
+/// to another deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanSnapshot {
+    pub plan: CarePlan,
+    pub goals: Vec<CareGoal>,
+    /// Progress entries of every goal, in recording order per goal.
+    pub progress: Vec<ProgressEntry>,
+    pub interventions: Vec<Intervention>,
+    pub barriers: Vec<Barrier>,
+    pub reviews: Vec<CareReview>,
+    pub care_team: Vec<CareTeamMember>,
+    /// Outcome side-table entries of achieved goals.
+    pub outcomes: Vec<GoalOutcomeRecord>,
+    pub exported_at: u64,
+}
+
+/// A goal's outcome notes and achievement times, as carried in a snapshot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalOutcomeRecord {
+    pub goal_id: u64,
+    pub achievement_date: Option<u64>,
+    pub outcome_notes: Option<String>,
+    /// Ledger time the goal was marked achieved.
+    pub achieved_at: Option<u64>,
+}
+
+// -----------------------------------------------------------------------
+// Legacy layouts (read only by the migration entrypoints)
+// -----------------------------------------------------------------------
+
+/// ProgressEntry as stored before recorded_by and numeric values existed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyProgressEntry {
+    pub goal_id: u64,
+    pub patient_id: Address,
+    pub current_value: String,
+    pub progress_note: String,
+    pub recorded_date: u64,
+}
+
+/// CareGoal as stored by the original contract release.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyCareGoal {
+    pub goal_id: u64,
+    pub care_plan_id: u64,
+    pub description: String,
+    pub target_value: Option<String>,
+    pub target_date: u64,
+    pub priority: Symbol,
+    pub status: GoalStatus,
+    pub progress_entries: Vec<LegacyProgressEntry>,
+    pub achievement_date: Option<u64>,
+    pub outcome_notes: Option<String>,
+    pub created_by: Address,
+    pub created_at: u64,
+}
+
+/// CarePlan as stored before coded conditions and end dates existed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyCarePlan {
+    pub care_plan_id: u64,
+    pub patient_id: Address,
+    pub provider_id: Address,
+    pub plan_type: Symbol,
+    pub conditions: Vec<String>,
+    pub goals: Vec<String>,
+    pub start_date: u64,
+    pub review_frequency_days: u32,
+    pub status: CarePlanStatus,
+    pub next_review_date: u64,
+    pub last_review_date: Option<u64>,
+    pub created_at: u64,
+}
+
+/// Intervention as stored before assignees, deactivation and goal links.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyIntervention {
+    pub intervention_id: u64,
+    pub care_plan_id: u64,
+    pub intervention_type: Symbol,
+    pub description: String,
+    pub frequency: String,
+    pub responsible_party: Symbol,
+    pub assigned_by: Address,
+    pub created_at: u64,
+}
+
+/// Barrier as stored before assignment and patient reporting.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyBarrier {
+    pub barrier_id: u64,
+    pub care_plan_id: u64,
+    pub reporter: Address,
+    pub barrier_type: Symbol,
+    pub description: String,
+    pub identified_date: u64,
+    pub resolved: bool,
+    pub resolution: Option<String>,
+    pub resolution_date: Option<u64>,
+    pub resolved_by: Option<Address>,
+}
+
+/// CareReview as stored before review kinds and cancellation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyCareReview {
+    pub review_id: u64,
+    pub care_plan_id: u64,
+    pub scheduled_by: Address,
+    pub review_date: u64,
+    pub review_type: Symbol,
+    pub conducted: bool,
+    pub review_notes_hash: Option<BytesN<32>>,
+    pub plan_modifications: Vec<String>,
+    pub continue_plan: bool,
+    pub conducted_by: Option<Address>,
+    pub conducted_at: Option<u64>,
 }
 
 // -----------------------------------------------------------------------
@@ -190,6 +532,24 @@ pub struct CarePlanSummary {
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
+    /// Contract administrator address.
+    Admin,
+    /// u32 schema/code version, bumped on each upgrade.
+    Version,
+    /// bool: patient-authored actions require recorded consent.
+    ConsentRequired,
+    /// bool: at most one Active plan per plan_type per patient.
+    SingleActivePlan,
+    /// bool: new plans wait for a second provider's co-signature.
+    CosignRequired,
+    /// u32: days a Completed plan is kept in full before auto-archiving.
+    ArchiveRetentionDays,
+    /// u64: seconds after marking during which an achievement can be retracted.
+    RetractionWindow,
+    /// u32: most goals a single care plan may hold.
+    MaxGoalsPerPlan,
+    /// u32: days past next_review_date before a plan's review counts as overdue.
+    ReviewGraceDays,
     /// Auto-increment counter for care plans.
     CarePlanCounter,
     /// Auto-increment counter for goals.
@@ -202,8 +562,12 @@ pub enum DataKey {
     ReviewCounter,
     /// care_plan_id -> CarePlan
     CarePlan(u64),
+    /// (provider_id, idempotency key) -> u64 (care plan created with it)
+    Idempotency(Address, BytesN<32>),
     /// goal_id -> CareGoal
     Goal(u64),
+    /// (goal_id, index) -> ProgressEntry
+    GoalProgress(u64, u32),
     /// intervention_id -> Intervention
     Intervention(u64),
     /// barrier_id -> Barrier
@@ -220,6 +584,61 @@ pub enum DataKey {
     PlanReviews(u64),
     /// care_plan_id -> Vec<CareTeamMember>
     PlanCareTeam(u64),
+    /// (care_plan_id, team_member) -> Symbol (role from the latest assignment)
+    TeamMemberRole(u64, Address),
     /// patient_id -> Vec<u64> (care plan ids)
     PatientPlans(Address),
+    /// provider_id -> Vec<u64> (care plan ids)
+    ProviderPlans(Address),
+    /// condition -> Vec<u64> (care plan ids listing it)
+    ConditionIndex(String),
+    /// condition code -> Vec<u64> (care plan ids coded with it)
+    ConditionCodeIndex(Symbol),
+    /// goal_id -> (achievement_date, outcome_notes)
+    GoalOutcome(u64),
+    /// goal_id -> Vec<GoalComment>
+    GoalComments(u64),
+    /// goal_id -> u64 (ledger time the goal was marked achieved)
+    GoalAchievedAt(u64),
+    /// goal_id -> Vec<AchievementRetraction>
+    GoalRetractions(u64),
+    /// care_plan_id -> Vec<String> (clinical guideline ids)
+    PlanGuidelines(u64),
+    /// (guideline_contract, guideline_id) -> u32 (number of care plans the
+    /// guideline is attached to)
+    GuidelineRefCount(Address, String),
+    /// care_plan_id -> Vec<(CarePlanStatus, u64)> (status, changed_at)
+    PlanStatusHistory(u64),
+    /// care_plan_id -> PlanConsent
+    PlanConsent(u64),
+    /// care_plan_id -> ArchivedPlan
+    Archive(u64),
+    /// care_plan_id -> CompletionReport
+    PlanCompletion(u64),
+    /// care_plan_id -> Map<Symbol, (u32, u32)> (barrier_type -> open, resolved)
+    BarrierStats(u64),
+    /// provider_id -> (u32, u32) (goals achieved, goals created)
+    ProviderGoalStats(Address),
+    /// care_plan_id -> EmergencyContact
+    EmergencyContact(u64),
+    /// (patient_id, metric) -> Vec<u64> (goal ids linked to that vital)
+    VitalGoals(Address, Symbol),
+    /// care_plan_id -> Map<Symbol, Vec<Symbol>> (team role -> permissions)
+    RolePermissions(u64),
+    /// intervention_id -> Vec<InterventionRating>
+    InterventionRatings(u64),
+    /// care_plan_id -> Vec<PlanNote>
+    PlanNotes(u64),
+    /// care_plan_id -> PlanCosignature
+    PlanCosignature(u64),
+    /// care_plan_id -> u32 (review grace days overriding ReviewGraceDays)
+    PlanReviewGraceDays(u64),
+}
+
+/// Contract-wide settings added once `DataKey` reached the variant limit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigKey {
+    /// Address: clinical-guideline contract plans may attach guidelines from.
+    GuidelineContract,
 }
\ No newline at end of file
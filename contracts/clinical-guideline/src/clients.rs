@@ -0,0 +1,29 @@
+use soroban_sdk::{Address, Env, String, Symbol, Vec, contractclient};
+
+// -----------------------------------------------------------------------
+// External contract interfaces
+// -----------------------------------------------------------------------
+
+/// Subset of `PatientVitalsContract` used to read recent renal function.
+#[allow(dead_code)]
+#[contractclient(name = "PatientVitalsClient")]
+pub trait PatientVitalsInterface {
+    fn get_vital_trend(
+        env: Env,
+        patient_id: Address,
+        metric: Symbol,
+        start: u64,
+        end: u64,
+    ) -> Vec<(u64, i64)>;
+}
+
+/// Subset of `CarePlanContract` used to check guideline references.
+#[allow(dead_code)]
+#[contractclient(name = "CarePlanClient")]
+pub trait CarePlanInterface {
+    fn guideline_reference_count(
+        env: Env,
+        guideline_contract: Address,
+        guideline_id: String,
+    ) -> u32;
+}
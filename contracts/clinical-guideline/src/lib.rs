@@ -1,8 +1,28 @@
 #![no_std]
 use soroban_sdk::{
-    Address, BytesN, Env, String, Symbol, Vec, contract, contracterror, contractimpl, contracttype,
+    Address, Bytes, BytesN, Env, Map, String, Symbol, Vec, contract, contracterror, contractevent,
+    contractimpl, contracttype, xdr::ToXdr,
 };
 
+mod clients;
+use clients::{CarePlanClient, PatientVitalsClient};
+
+// Plausible patient weights; values outside usually mean kilograms were passed
+const MIN_WEIGHT_GRAMS: u64 = 500;
+const MAX_WEIGHT_GRAMS: u64 = 500_000;
+
+// Ledgers a cached evaluation lives in temporary storage (about an hour)
+const EVAL_CACHE_TTL_LEDGERS: u32 = 720;
+
+// How far back GFR readings are considered when dosing (30 days)
+const RENAL_LOOKBACK_SECS: u64 = 30 * 24 * 60 * 60;
+
+// Largest patient panel screened in one preventive-care batch
+const MAX_PREVENTIVE_BATCH: u32 = 50;
+
+// Most reminders kept in one patient's index
+const MAX_PATIENT_REMINDERS: u32 = 100;
+
 // --- Custom Error Types ---
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -11,18 +31,45 @@ pub enum Error {
     NotAuthorized = 1,
     GuidelineNotFound = 2,
     InvalidInput = 3,
+    ReminderNotFound = 4,
+    ReminderAlreadyCompleted = 5,
+    AlreadyInitialized = 6,
+    Contraindicated = 7,
+    GuidelineInUse = 8,
+    GuidelineAlreadyExists = 9,
+    CarePlanContractNotSet = 10,
+    ReminderLimitReached = 11,
 }
 
 // --- Data Structures ---
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EvidenceLevel {
+    A,
+    B,
+    C,
+    D,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecommendationStrength {
+    Strong,
+    Moderate,
+    Weak,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct GuidelineRecommendation {
     pub guideline_id: String,
     pub applicable: bool,
     pub recommendation: String,
-    pub strength: Symbol,
-    pub evidence_level: Symbol,
+    pub strength: RecommendationStrength,
+    pub evidence_level: EvidenceLevel,
     pub alternative_options: Vec<String>,
+    pub reason: Option<String>,
+    pub matched_count: u32,
 }
 
 #[contracttype]
@@ -30,6 +77,10 @@ pub struct GuidelineRecommendation {
 pub struct DosageRecommendation {
     pub medication: String,
     pub recommended_dose: String,
+    // Computed dose in mg for the given weight, after renal adjustment
+    pub dose_mg: u64,
+    // Set when the computed dose was clamped to the protocol's max_dose_mg
+    pub capped: bool,
     pub frequency: String,
     pub route: Symbol,
     pub duration: Option<u64>,
@@ -37,6 +88,68 @@ pub struct DosageRecommendation {
     pub monitoring_required: Vec<String>,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EvaluationLog {
+    pub guideline_id: String,
+    pub patient_id: Address,
+    pub provider_id: Address,
+    pub applicable: bool,
+    pub evaluated_at: u64,
+}
+
+// Cached evaluation, valid only while the guideline record hashes the same, so
+// updates, deprecation and removal followed by re-registration all invalidate it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedEvaluation {
+    pub guideline_hash: BytesN<32>,
+    pub recommendation: GuidelineRecommendation,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DosageProtocol {
+    pub medication: String,
+    // Doses are mg per kg scaled by 1000 (fixed-point)
+    pub adult_mg_per_kg_x1000: u64,
+    pub pediatric_mg_per_kg_x1000: u64,
+    // Patients younger than this use the pediatric band
+    pub adult_min_age: u32,
+    // Patients younger than this must not receive the medication at all
+    pub min_age: u32,
+    pub max_dose_mg: u64,
+    // Percent of the computed dose given at GFR 30-59 and GFR < 30
+    pub renal_moderate_pct: u32,
+    pub renal_severe_pct: u32,
+    pub frequency: String,
+    pub route: Symbol,
+    pub duration: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskCalculator {
+    pub weights: Vec<i32>,
+    // One name per weight, used to label each parameter's contribution
+    pub parameter_names: Vec<Symbol>,
+    // Scores at or above these bounds are moderate / high risk
+    pub moderate_threshold: i32,
+    pub high_threshold: i32,
+    // Bumped each time the calculator is recalibrated
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskResult {
+    pub score: i32,
+    pub category: Symbol,
+    pub components: Vec<(Symbol, i32)>,
+    // Calculator version that produced this score
+    pub version: u32,
+}
+
 // Placeholder for logic-heavy structures
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -45,135 +158,1274 @@ pub struct CarePathway {
     pub steps: Vec<String>,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathwayNode {
+    pub step: String,
+    // Decision symbol (e.g. a test result) -> index of the next node
+    pub branches: Map<Symbol, u32>,
+    // Followed when no branch matches the decision; None ends the pathway here
+    pub default_next: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Guideline {
+    pub guideline_id: String,
+    pub condition: String,
+    pub criteria_hashes: Vec<BytesN<32>>,
+    // Minimum number of criteria a patient must match for applicability
+    pub min_criteria_match: u32,
+    pub recommendation_hash: BytesN<32>,
+    pub recommendation: String,
+    pub strength: RecommendationStrength,
+    pub evidence_level: EvidenceLevel,
+    pub alternative_options: Vec<String>,
+    // Medication to dose when the guideline applies, keyed into the dosage table
+    pub medication: Option<String>,
+    pub version: u32,
+    pub registered_at: u64,
+    pub active: bool,
+    pub deprecation_reason: Option<String>,
+}
+
+// Caller-supplied fields of a new guideline
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuidelineInput {
+    pub guideline_id: String,
+    pub condition: String,
+    pub criteria_hashes: Vec<BytesN<32>>,
+    pub min_criteria_match: u32,
+    pub recommendation_hash: BytesN<32>,
+    pub recommendation: String,
+    pub strength: RecommendationStrength,
+    pub evidence_level: EvidenceLevel,
+    pub alternative_options: Vec<String>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reminder {
+    pub reminder_id: u64,
+    pub patient_id: Address,
+    pub provider_id: Address,
+    pub reminder_type: Symbol,
+    pub due_date: u64,
+    // One of high / medium / low
+    pub priority: Symbol,
+    // When set, completing the reminder schedules the next occurrence
+    pub recurrence_days: Option<u32>,
+    pub created_at: u64,
+    pub completed_at: Option<u64>,
+}
+
+// --- Events ---
+#[contractevent]
+pub struct GuidelineRegistered {
+    #[topic]
+    pub guideline_id: String,
+    pub condition: String,
+    pub version: u32,
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct GuidelineEvaluated {
+    #[topic]
+    pub patient_id: Address,
+    pub guideline_id: String,
+    pub applicable: bool,
+    pub matched_count: u32,
+}
+
+#[contractevent]
+pub struct GuidelineRemoved {
+    pub guideline_id: String,
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct DosageCalculated {
+    #[topic]
+    pub patient_id: Address,
+    pub medication: String,
+    pub dose_mg: u64,
+    pub renal_adjustment: bool,
+}
+
+#[contractevent]
+pub struct PathwayAdvanced {
+    #[topic]
+    pub patient_id: Address,
+    pub condition: String,
+    pub step: u32,
+    pub provider_id: Address,
+}
+
+#[contractevent]
+pub struct ReminderCreated {
+    #[topic]
+    pub patient_id: Address,
+    pub reminder_id: u64,
+    pub reminder_type: Symbol,
+    pub due_date: u64,
+}
+
+// --- Storage Keys ---
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Guideline(String),
+    GuidelineVersion(String, u32),
+    DosageProtocol(String),
+    RiskCalculator(Symbol, u32),
+    // Latest registered version of a risk calculator
+    RiskCalculatorVersion(Symbol),
+    Pathway(String),
+    // Index of the patient's current step in a condition's pathway
+    PathwayProgress(Address, String),
+    BranchedPathway(String),
+    // Index of the patient's current node in a condition's branched pathway
+    PathwayNodeProgress(Address, String),
+    Interaction(String, String),
+    ReminderCounter,
+    Reminder(u64),
+    PatientReminders(Address),
+    EvalLog(Address),
+    // Temporary: (guideline_id, hash of patient attributes) -> CachedEvaluation
+    EvalCache(String, BytesN<32>),
+    // PatientVitalsContract used for recent GFR readings
+    VitalsContract,
+    // CarePlanContract consulted before a guideline is removed
+    CarePlanContract,
+}
+
 #[contract]
 pub struct ClinicalGuidelineContract;
 
 #[contractimpl]
 impl ClinicalGuidelineContract {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
     pub fn register_clinical_guideline(
         env: Env,
         admin: Address,
-        guideline_id: String,
-        _condition: String,
-        criteria_hash: BytesN<32>,
-        _recommendation_hash: BytesN<32>,
-        _evidence_level: Symbol,
+        input: GuidelineInput,
     ) -> Result<(), Error> {
-        admin.require_auth();
-        // Use guideline_id as the storage key
+        Self::require_admin(&env, &admin)?;
+        let GuidelineInput {
+            guideline_id,
+            condition,
+            criteria_hashes,
+            min_criteria_match,
+            recommendation_hash,
+            recommendation,
+            strength,
+            evidence_level,
+            alternative_options,
+        } = input;
+        Self::validate_criteria(&criteria_hashes, min_criteria_match)?;
+        // Changes to an existing guideline go through update_guideline so the
+        // previous version is kept
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Guideline(guideline_id.clone()))
+        {
+            return Err(Error::GuidelineAlreadyExists);
+        }
+
+        let guideline = Guideline {
+            guideline_id: guideline_id.clone(),
+            condition,
+            criteria_hashes,
+            min_criteria_match,
+            recommendation_hash,
+            recommendation,
+            strength,
+            evidence_level,
+            alternative_options,
+            medication: None,
+            version: 1,
+            registered_at: env.ledger().timestamp(),
+            active: true,
+            deprecation_reason: None,
+        };
         env.storage()
             .persistent()
-            .set(&guideline_id, &criteria_hash);
+            .set(&DataKey::Guideline(guideline_id.clone()), &guideline);
+
+        GuidelineRegistered {
+            guideline_id,
+            condition: guideline.condition,
+            version: guideline.version,
+            admin,
+        }
+        .publish(&env);
         Ok(())
     }
 
     pub fn evaluate_guideline(
         env: Env,
-        _patient_id: Address,
-        _provider_id: Address,
+        patient_id: Address,
+        provider_id: Address,
         guideline_id: String,
-        patient_data_hash: BytesN<32>,
+        patient_attributes: Vec<BytesN<32>>,
+        log: bool,
+        bypass_cache: bool,
     ) -> Result<GuidelineRecommendation, Error> {
-        // Retrieve stored criteria
-        let stored_hash: BytesN<32> = env
+        let guideline: Guideline = env
             .storage()
             .persistent()
-            .get(&guideline_id)
+            .get(&DataKey::Guideline(guideline_id.clone()))
             .ok_or(Error::GuidelineNotFound)?;
 
-        let is_applicable = stored_hash == patient_data_hash;
+        let mut data = Bytes::new(&env);
+        for attribute in patient_attributes.iter() {
+            data.extend_from_array(&attribute.to_array());
+        }
+        let cache_key =
+            DataKey::EvalCache(guideline_id.clone(), env.crypto().sha256(&data).to_bytes());
+        let guideline_hash = env
+            .crypto()
+            .sha256(&guideline.clone().to_xdr(&env))
+            .to_bytes();
+        let cached = if bypass_cache {
+            None
+        } else {
+            env.storage()
+                .temporary()
+                .get::<_, CachedEvaluation>(&cache_key)
+                .filter(|c| c.guideline_hash == guideline_hash)
+        };
+
+        let recommendation = match cached {
+            Some(hit) => hit.recommendation,
+            None => {
+                let mut matched_count: u32 = 0;
+                for criterion in guideline.criteria_hashes.iter() {
+                    if patient_attributes.contains(&criterion) {
+                        matched_count += 1;
+                    }
+                }
+                let recommendation = GuidelineRecommendation {
+                    guideline_id: guideline_id.clone(),
+                    applicable: guideline.active && matched_count >= guideline.min_criteria_match,
+                    recommendation: guideline.recommendation,
+                    strength: guideline.strength,
+                    evidence_level: guideline.evidence_level,
+                    alternative_options: guideline.alternative_options,
+                    reason: guideline.deprecation_reason,
+                    matched_count,
+                };
+                env.storage().temporary().set(
+                    &cache_key,
+                    &CachedEvaluation {
+                        guideline_hash,
+                        recommendation: recommendation.clone(),
+                    },
+                );
+                env.storage().temporary().extend_ttl(
+                    &cache_key,
+                    EVAL_CACHE_TTL_LEDGERS,
+                    EVAL_CACHE_TTL_LEDGERS,
+                );
+                recommendation
+            }
+        };
 
-        Ok(GuidelineRecommendation {
+        GuidelineEvaluated {
+            patient_id: patient_id.clone(),
+            guideline_id: guideline_id.clone(),
+            applicable: recommendation.applicable,
+            matched_count: recommendation.matched_count,
+        }
+        .publish(&env);
+
+        // Audit trail of which guidelines were checked for a patient
+        if log {
+            provider_id.require_auth();
+            let key = DataKey::EvalLog(patient_id.clone());
+            let mut history: Vec<EvaluationLog> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(&env));
+            history.push_back(EvaluationLog {
+                guideline_id,
+                patient_id,
+                provider_id,
+                applicable: recommendation.applicable,
+                evaluated_at: env.ledger().timestamp(),
+            });
+            env.storage().persistent().set(&key, &history);
+        }
+
+        Ok(recommendation)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn recommend_treatment(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        guideline_id: String,
+        patient_attributes: Vec<BytesN<32>>,
+        weight_grams: u64,
+        age: u32,
+        renal_function: Option<u32>,
+    ) -> Result<(GuidelineRecommendation, Option<DosageRecommendation>), Error> {
+        let medication = Self::get_guideline(env.clone(), guideline_id.clone())?.medication;
+        let recommendation = Self::evaluate_guideline(
+            env.clone(),
+            patient_id.clone(),
+            provider_id,
             guideline_id,
-            applicable: is_applicable,
-            recommendation: String::from_str(&env, "Follow Standard Protocol"),
-            strength: Symbol::new(&env, "High"),
-            evidence_level: Symbol::new(&env, "Level_A"),
-            alternative_options: Vec::new(&env),
-        })
+            patient_attributes,
+            false,
+            false,
+        )?;
+
+        // Only dose when the guideline applies and names a medication
+        let dosage = match medication {
+            Some(medication) if recommendation.applicable => Some(Self::calculate_drug_dosage(
+                env,
+                patient_id,
+                medication,
+                weight_grams,
+                age,
+                renal_function,
+            )?),
+            _ => None,
+        };
+        Ok((recommendation, dosage))
+    }
+
+    pub fn get_eval_history(env: Env, patient_id: Address) -> Vec<EvaluationLog> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EvalLog(patient_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn update_guideline(
+        env: Env,
+        admin: Address,
+        guideline_id: String,
+        new_criteria_hashes: Vec<BytesN<32>>,
+        new_min_criteria_match: u32,
+        new_recommendation_hash: BytesN<32>,
+        new_evidence_level: EvidenceLevel,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env, &admin)?;
+        Self::validate_criteria(&new_criteria_hashes, new_min_criteria_match)?;
+
+        let key = DataKey::Guideline(guideline_id.clone());
+        let mut guideline: Guideline = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::GuidelineNotFound)?;
+
+        // Keep the superseded version for historical lookups
+        env.storage().persistent().set(
+            &DataKey::GuidelineVersion(guideline_id, guideline.version),
+            &guideline,
+        );
+
+        guideline.criteria_hashes = new_criteria_hashes;
+        guideline.min_criteria_match = new_min_criteria_match;
+        guideline.recommendation_hash = new_recommendation_hash;
+        guideline.evidence_level = new_evidence_level;
+        guideline.version += 1;
+        guideline.registered_at = env.ledger().timestamp();
+
+        env.storage().persistent().set(&key, &guideline);
+        Ok(guideline.version)
+    }
+
+    pub fn set_guideline_medication(
+        env: Env,
+        admin: Address,
+        guideline_id: String,
+        medication: Option<String>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::Guideline(guideline_id);
+        let mut guideline: Guideline = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::GuidelineNotFound)?;
+
+        guideline.medication = medication;
+        env.storage().persistent().set(&key, &guideline);
+        Ok(())
+    }
+
+    pub fn deprecate_guideline(
+        env: Env,
+        admin: Address,
+        guideline_id: String,
+        reason: String,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::Guideline(guideline_id);
+        let mut guideline: Guideline = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::GuidelineNotFound)?;
+
+        guideline.active = false;
+        guideline.deprecation_reason = Some(reason);
+
+        env.storage().persistent().set(&key, &guideline);
+        Ok(())
+    }
+
+    pub fn set_care_plan_contract(
+        env: Env,
+        admin: Address,
+        care_plan_contract: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::CarePlanContract, &care_plan_contract);
+        Ok(())
+    }
+
+    pub fn remove_guideline(env: Env, admin: Address, guideline_id: String) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::Guideline(guideline_id.clone());
+        let guideline: Guideline = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::GuidelineNotFound)?;
+
+        // Care plans keep guideline ids, so removing a referenced one would orphan
+        // them; without a care plan contract to ask, refuse rather than guess
+        let care_plan: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CarePlanContract)
+            .ok_or(Error::CarePlanContractNotSet)?;
+        let references = CarePlanClient::new(&env, &care_plan)
+            .guideline_reference_count(&env.current_contract_address(), &guideline_id);
+        if references > 0 {
+            return Err(Error::GuidelineInUse);
+        }
+
+        for version in 1..guideline.version {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::GuidelineVersion(guideline_id.clone(), version));
+        }
+        env.storage().persistent().remove(&key);
+
+        GuidelineRemoved {
+            guideline_id,
+            admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_guideline(env: Env, guideline_id: String) -> Result<Guideline, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Guideline(guideline_id))
+            .ok_or(Error::GuidelineNotFound)
+    }
+
+    pub fn get_guideline_version(
+        env: Env,
+        guideline_id: String,
+        version: u32,
+    ) -> Result<Guideline, Error> {
+        let current = Self::get_guideline(env.clone(), guideline_id.clone())?;
+        if current.version == version {
+            return Ok(current);
+        }
+
+        env.storage()
+            .persistent()
+            .get(&DataKey::GuidelineVersion(guideline_id, version))
+            .ok_or(Error::GuidelineNotFound)
+    }
+
+    pub fn register_dosage_protocol(
+        env: Env,
+        admin: Address,
+        protocol: DosageProtocol,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if protocol.adult_mg_per_kg_x1000 == 0
+            || protocol.pediatric_mg_per_kg_x1000 == 0
+            || protocol.max_dose_mg == 0
+        {
+            return Err(Error::InvalidInput);
+        }
+        if protocol.renal_moderate_pct > 100 || protocol.renal_severe_pct > 100 {
+            return Err(Error::InvalidInput);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::DosageProtocol(protocol.medication.clone()), &protocol);
+        Ok(())
+    }
+
+    pub fn set_vitals_contract(
+        env: Env,
+        admin: Address,
+        vitals_contract: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::VitalsContract, &vitals_contract);
+        Ok(())
     }
 
     pub fn calculate_drug_dosage(
         env: Env,
-        _patient_id: Address,
+        patient_id: Address,
         medication: String,
         weight_grams: u64, // Used u64 for fixed-point math instead of f32
-        _age: u32,
+        age: u32,
         renal_function: Option<u32>,
     ) -> Result<DosageRecommendation, Error> {
-        // Simple example: 5mg per kg (1000g)
-        let _dose_mg = (weight_grams * 5) / 1000;
-        let is_renal_impaired = renal_function.unwrap_or(100) < 60;
+        if !(MIN_WEIGHT_GRAMS..=MAX_WEIGHT_GRAMS).contains(&weight_grams) {
+            return Err(Error::InvalidInput);
+        }
+
+        let protocol: DosageProtocol = env
+            .storage()
+            .persistent()
+            .get(&DataKey::DosageProtocol(medication.clone()))
+            .ok_or(Error::GuidelineNotFound)?;
+
+        if age < protocol.min_age {
+            return Err(Error::Contraindicated);
+        }
+
+        let mg_per_kg_x1000 = if age < protocol.adult_min_age {
+            protocol.pediatric_mg_per_kg_x1000
+        } else {
+            protocol.adult_mg_per_kg_x1000
+        };
+
+        // grams * (mg/kg * 1000) / 1_000_000 = mg
+        let mut dose_mg = weight_grams
+            .checked_mul(mg_per_kg_x1000)
+            .ok_or(Error::InvalidInput)?
+            / 1_000_000;
+
+        // The worst recent reading wins so a transient improvement can't mask impairment
+        let gfr = Self::worst_recent_gfr(&env, &patient_id, renal_function).unwrap_or(100);
+        let is_renal_impaired = gfr < 60;
+        let mut monitoring_required = Vec::new(&env);
+        if is_renal_impaired {
+            let pct = if gfr < 30 {
+                protocol.renal_severe_pct
+            } else {
+                protocol.renal_moderate_pct
+            };
+            dose_mg = dose_mg * pct as u64 / 100;
+            monitoring_required.push_back(String::from_str(&env, "Serum_Creatinine"));
+        }
+
+        let capped = dose_mg > protocol.max_dose_mg;
+        if capped {
+            dose_mg = protocol.max_dose_mg;
+        }
+
+        DosageCalculated {
+            patient_id,
+            medication: medication.clone(),
+            dose_mg,
+            renal_adjustment: is_renal_impaired,
+        }
+        .publish(&env);
 
         Ok(DosageRecommendation {
             medication,
-            recommended_dose: String::from_str(&env, "5mg/kg"),
-            frequency: String::from_str(&env, "QD"),
-            route: Symbol::new(&env, "Oral"),
-            duration: Some(10),
+            recommended_dose: Self::format_mg(&env, dose_mg),
+            dose_mg,
+            capped,
+            frequency: protocol.frequency,
+            route: protocol.route,
+            duration: protocol.duration,
             renal_adjustment: is_renal_impaired,
-            monitoring_required: Vec::new(&env),
+            monitoring_required,
         })
     }
 
+    pub fn register_risk_calculator(
+        env: Env,
+        admin: Address,
+        calculator: Symbol,
+        weights: Vec<i32>,
+        parameter_names: Vec<Symbol>,
+        moderate_threshold: i32,
+        high_threshold: i32,
+    ) -> Result<u32, Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if weights.is_empty()
+            || parameter_names.len() != weights.len()
+            || moderate_threshold > high_threshold
+        {
+            return Err(Error::InvalidInput);
+        }
+
+        // Earlier versions stay stored so past scores can be reproduced
+        let version_key = DataKey::RiskCalculatorVersion(calculator.clone());
+        let version = env
+            .storage()
+            .persistent()
+            .get::<_, u32>(&version_key)
+            .unwrap_or(0)
+            + 1;
+        let entry = RiskCalculator {
+            weights,
+            parameter_names,
+            moderate_threshold,
+            high_threshold,
+            version,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::RiskCalculator(calculator, version), &entry);
+        env.storage().persistent().set(&version_key, &version);
+        Ok(version)
+    }
+
+    pub fn get_risk_calculator(
+        env: Env,
+        calculator: Symbol,
+        version: Option<u32>,
+    ) -> Result<RiskCalculator, Error> {
+        let version = match version {
+            Some(version) => version,
+            None => env
+                .storage()
+                .persistent()
+                .get(&DataKey::RiskCalculatorVersion(calculator.clone()))
+                .ok_or(Error::GuidelineNotFound)?,
+        };
+        env.storage()
+            .persistent()
+            .get(&DataKey::RiskCalculator(calculator, version))
+            .ok_or(Error::GuidelineNotFound)
+    }
+
+    pub fn register_interaction(
+        env: Env,
+        admin: Address,
+        drug_a: String,
+        drug_b: String,
+        severity: Symbol,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if drug_a == drug_b {
+            return Err(Error::InvalidInput);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Self::interaction_key(drug_a, drug_b), &severity);
+        Ok(())
+    }
+
+    pub fn check_interactions(env: Env, medications: Vec<String>) -> Vec<(String, String, Symbol)> {
+        let mut interactions = Vec::new(&env);
+        let count = medications.len();
+        for i in 0..count {
+            let drug_a = medications.get_unchecked(i);
+            for j in (i + 1)..count {
+                let drug_b = medications.get_unchecked(j);
+                let severity: Option<Symbol> = env
+                    .storage()
+                    .persistent()
+                    .get(&Self::interaction_key(drug_a.clone(), drug_b.clone()));
+                if let Some(severity) = severity {
+                    interactions.push_back((drug_a.clone(), drug_b, severity));
+                }
+            }
+        }
+        interactions
+    }
+
     pub fn assess_risk_score(
+        env: Env,
+        patient_id: Address,
+        risk_calculator: Symbol,
+        input_parameters: Vec<i32>,
+        version: Option<u32>,
+    ) -> Result<(i32, Symbol), Error> {
+        let result =
+            Self::assess_risk(env, patient_id, risk_calculator, input_parameters, version)?;
+        Ok((result.score, result.category))
+    }
+
+    pub fn assess_risk(
         env: Env,
         _patient_id: Address,
-        _risk_calculator: Symbol,
+        risk_calculator: Symbol,
         input_parameters: Vec<i32>,
-    ) -> Result<i32, Error> {
+        version: Option<u32>,
+    ) -> Result<RiskResult, Error> {
+        // No version means the latest calibration
+        let calculator = Self::get_risk_calculator(env.clone(), risk_calculator, version)?;
+
+        if input_parameters.len() != calculator.weights.len() {
+            return Err(Error::InvalidInput);
+        }
+
+        // Weighted dot product of inputs and registered weights, keeping each term
         let mut total_score: i32 = 0;
-        for val in input_parameters.iter() {
-            total_score += val;
+        let mut components = Vec::new(&env);
+        for i in 0..calculator.weights.len() {
+            let term = input_parameters
+                .get_unchecked(i)
+                .checked_mul(calculator.weights.get_unchecked(i))
+                .ok_or(Error::InvalidInput)?;
+            total_score = total_score.checked_add(term).ok_or(Error::InvalidInput)?;
+            components.push_back((calculator.parameter_names.get_unchecked(i), term));
         }
-        Ok(total_score)
+
+        let category = if total_score >= calculator.high_threshold {
+            Symbol::new(&env, "high")
+        } else if total_score >= calculator.moderate_threshold {
+            Symbol::new(&env, "moderate")
+        } else {
+            Symbol::new(&env, "low")
+        };
+
+        Ok(RiskResult {
+            score: total_score,
+            category,
+            components,
+            version: calculator.version,
+        })
+    }
+
+    pub fn register_care_pathway(
+        env: Env,
+        admin: Address,
+        condition: String,
+        steps: Vec<String>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if steps.is_empty() {
+            return Err(Error::InvalidInput);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pathway(condition), &steps);
+        Ok(())
     }
 
     pub fn suggest_care_pathway(
         env: Env,
         _patient_id: Address,
         condition: String,
-        _current_treatment: Vec<String>,
+        current_treatment: Vec<String>,
     ) -> Result<CarePathway, Error> {
+        let registered: Option<Vec<String>> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Pathway(condition.clone()));
+
+        let all_steps = registered.unwrap_or_else(|| {
+            // Generic fallback when no pathway is registered for the condition
+            let mut generic = Vec::new(&env);
+            generic.push_back(String::from_str(&env, "Initial Assessment"));
+            generic.push_back(String::from_str(&env, "Lab Tests"));
+            generic
+        });
+
+        // Skip steps the patient has already completed
         let mut steps = Vec::new(&env);
-        steps.push_back(String::from_str(&env, "Initial Assessment"));
-        steps.push_back(String::from_str(&env, "Lab Tests"));
+        for step in all_steps.iter() {
+            if !current_treatment.contains(&step) {
+                steps.push_back(step);
+            }
+        }
 
         Ok(CarePathway { condition, steps })
     }
 
+    pub fn advance_pathway(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        condition: String,
+    ) -> Result<u32, Error> {
+        provider_id.require_auth();
+
+        let steps: Vec<String> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Pathway(condition.clone()))
+            .ok_or(Error::GuidelineNotFound)?;
+
+        let key = DataKey::PathwayProgress(patient_id.clone(), condition.clone());
+        let current: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        // Already past the final step
+        if current >= steps.len() {
+            return Err(Error::InvalidInput);
+        }
+
+        let next = current + 1;
+        env.storage().persistent().set(&key, &next);
+
+        PathwayAdvanced {
+            patient_id,
+            condition,
+            step: next,
+            provider_id,
+        }
+        .publish(&env);
+        Ok(next)
+    }
+
+    pub fn get_pathway_progress(env: Env, patient_id: Address, condition: String) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PathwayProgress(patient_id, condition))
+            .unwrap_or(0)
+    }
+
+    pub fn register_branched_pathway(
+        env: Env,
+        admin: Address,
+        condition: String,
+        nodes: Vec<PathwayNode>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        if nodes.is_empty() {
+            return Err(Error::InvalidInput);
+        }
+        // Every branch must land on a node in this pathway
+        for node in nodes.iter() {
+            if node.default_next.is_some_and(|next| next >= nodes.len()) {
+                return Err(Error::InvalidInput);
+            }
+            for next in node.branches.values().iter() {
+                if next >= nodes.len() {
+                    return Err(Error::InvalidInput);
+                }
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::BranchedPathway(condition), &nodes);
+        Ok(())
+    }
+
+    pub fn next_pathway_step(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        condition: String,
+        decision: Symbol,
+    ) -> Result<String, Error> {
+        provider_id.require_auth();
+
+        let nodes: Vec<PathwayNode> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BranchedPathway(condition.clone()))
+            .ok_or(Error::GuidelineNotFound)?;
+
+        let key = DataKey::PathwayNodeProgress(patient_id.clone(), condition.clone());
+        let current: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        let node = nodes.get(current).ok_or(Error::InvalidInput)?;
+
+        // No matching branch and no default means the pathway has ended
+        let next = node
+            .branches
+            .get(decision)
+            .or(node.default_next)
+            .ok_or(Error::InvalidInput)?;
+        env.storage().persistent().set(&key, &next);
+
+        PathwayAdvanced {
+            patient_id,
+            condition,
+            step: next,
+            provider_id,
+        }
+        .publish(&env);
+        Ok(nodes.get(next).ok_or(Error::InvalidInput)?.step)
+    }
+
+    pub fn get_pathway_node(env: Env, patient_id: Address, condition: String) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PathwayNodeProgress(patient_id, condition))
+            .unwrap_or(0)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_reminder(
         env: Env,
+        caller: Address,
         patient_id: Address,
-        _provider_id: Address,
-        _reminder_type: Symbol,
+        provider_id: Address,
+        reminder_type: Symbol,
         due_date: u64,
-        _priority: Symbol,
+        priority: Symbol,
+        recurrence_days: Option<u32>,
     ) -> Result<u64, Error> {
-        // Use ledger timestamp + patient address hash as a simple ID
-        let reminder_id = env.ledger().timestamp();
-        env.storage().temporary().set(&patient_id, &due_date);
-        Ok(reminder_id)
+        caller.require_auth();
+        if caller != patient_id && caller != provider_id {
+            return Err(Error::NotAuthorized);
+        }
+        if Self::priority_rank(&env, &priority).is_none() || recurrence_days == Some(0) {
+            return Err(Error::InvalidInput);
+        }
+
+        Self::store_reminder(
+            &env,
+            patient_id,
+            provider_id,
+            reminder_type,
+            due_date,
+            priority,
+            recurrence_days,
+        )
+    }
+
+    pub fn get_reminders(env: Env, patient_id: Address) -> Vec<Reminder> {
+        let ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PatientReminders(patient_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut reminders = Vec::new(&env);
+        for id in ids.iter() {
+            if let Some(reminder) = env.storage().persistent().get(&DataKey::Reminder(id)) {
+                reminders.push_back(reminder);
+            }
+        }
+        reminders
+    }
+
+    pub fn complete_reminder(
+        env: Env,
+        reminder_id: u64,
+        patient_id: Address,
+    ) -> Result<Option<u64>, Error> {
+        patient_id.require_auth();
+
+        let key = DataKey::Reminder(reminder_id);
+        let mut reminder: Reminder = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::ReminderNotFound)?;
+
+        if reminder.patient_id != patient_id {
+            return Err(Error::NotAuthorized);
+        }
+        if reminder.completed_at.is_some() {
+            return Err(Error::ReminderAlreadyCompleted);
+        }
+
+        reminder.completed_at = Some(env.ledger().timestamp());
+        env.storage().persistent().set(&key, &reminder);
+
+        // Recurring reminders roll forward from the completed due date
+        let Some(days) = reminder.recurrence_days else {
+            return Ok(None);
+        };
+        let next_due = (days as u64)
+            .checked_mul(86400)
+            .and_then(|secs| reminder.due_date.checked_add(secs))
+            .ok_or(Error::InvalidInput)?;
+        let next_id = Self::store_reminder(
+            &env,
+            reminder.patient_id,
+            reminder.provider_id,
+            reminder.reminder_type,
+            next_due,
+            reminder.priority,
+            reminder.recurrence_days,
+        )?;
+        Ok(Some(next_id))
+    }
+
+    pub fn list_due_reminders(env: Env, patient_id: Address, as_of: u64) -> Vec<Reminder> {
+        let mut due = Vec::new(&env);
+        for reminder in Self::get_reminders(env.clone(), patient_id).iter() {
+            if reminder.completed_at.is_none() && reminder.due_date <= as_of {
+                due.push_back(reminder);
+            }
+        }
+        due
+    }
+
+    pub fn list_reminders_sorted(env: Env, patient_id: Address) -> Vec<Reminder> {
+        // Insertion sort by (priority rank, due date); earlier entries win ties
+        let mut sorted: Vec<Reminder> = Vec::new(&env);
+        for reminder in Self::get_reminders(env.clone(), patient_id).iter() {
+            let key = Self::reminder_sort_key(&env, &reminder);
+            let mut pos = sorted.len();
+            while pos > 0 && Self::reminder_sort_key(&env, &sorted.get_unchecked(pos - 1)) > key {
+                pos -= 1;
+            }
+            sorted.insert(pos, reminder);
+        }
+        sorted
     }
 
     pub fn check_preventive_care(
         env: Env,
         _patient_id: Address,
         age: u32,
-        _gender: Symbol,
-        _risk_factors: Vec<Symbol>,
+        gender: Symbol,
+        risk_factors: Vec<Symbol>,
     ) -> Result<Vec<Symbol>, Error> {
         let mut alerts = Vec::new(&env);
 
         if age > 45 {
-            alerts.push_back(Symbol::new(&env, "Cardiac_Screening"));
+            Self::push_unique(&mut alerts, Symbol::new(&env, "Cardiac_Screening"));
         }
         if age > 18 {
-            alerts.push_back(Symbol::new(&env, "Blood_Pressure_Check"));
+            Self::push_unique(&mut alerts, Symbol::new(&env, "Blood_Pressure_Check"));
+        }
+
+        // Gender-specific screenings
+        let is_smoker = risk_factors.contains(Symbol::new(&env, "smoker"));
+        if gender == Symbol::new(&env, "F") && age > 40 {
+            Self::push_unique(&mut alerts, Symbol::new(&env, "Mammography"));
+        }
+        if gender == Symbol::new(&env, "M") && age > 65 && is_smoker {
+            Self::push_unique(&mut alerts, Symbol::new(&env, "AAA_Screening"));
+        }
+
+        // Risk-factor triggered screenings
+        for factor in risk_factors.iter() {
+            if factor == Symbol::new(&env, "diabetes") {
+                Self::push_unique(&mut alerts, Symbol::new(&env, "Eye_Exam"));
+                Self::push_unique(&mut alerts, Symbol::new(&env, "Foot_Exam"));
+            } else if factor == Symbol::new(&env, "hypertension") {
+                Self::push_unique(&mut alerts, Symbol::new(&env, "Blood_Pressure_Check"));
+                Self::push_unique(&mut alerts, Symbol::new(&env, "Kidney_Function"));
+            } else if factor == Symbol::new(&env, "smoker") && age >= 50 {
+                Self::push_unique(&mut alerts, Symbol::new(&env, "Lung_Cancer_Screening"));
+            }
         }
 
         Ok(alerts)
     }
+
+    pub fn check_preventive_care_batch(
+        env: Env,
+        patients: Vec<(Address, u32, Symbol, Vec<Symbol>)>,
+    ) -> Result<Vec<Vec<Symbol>>, Error> {
+        if patients.len() > MAX_PREVENTIVE_BATCH {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut results = Vec::new(&env);
+        for (patient_id, age, gender, risk_factors) in patients.iter() {
+            let alerts =
+                Self::check_preventive_care(env.clone(), patient_id, age, gender, risk_factors)?;
+            results.push_back(alerts);
+        }
+        Ok(results)
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotAuthorized)?;
+        if stored != *admin {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn worst_recent_gfr(env: &Env, patient_id: &Address, reported: Option<u32>) -> Option<u32> {
+        let linked: Option<Address> = env.storage().persistent().get(&DataKey::VitalsContract);
+        let Some(vitals_contract) = linked else {
+            return reported;
+        };
+
+        let now = env.ledger().timestamp();
+        let start = now.saturating_sub(RENAL_LOOKBACK_SECS);
+        let metric = Symbol::new(env, "gfr");
+        let trend = match PatientVitalsClient::new(env, &vitals_contract)
+            .try_get_vital_trend(patient_id, &metric, &start, &now)
+        {
+            Ok(Ok(trend)) => trend,
+            _ => return reported,
+        };
+
+        let mut worst = reported;
+        for (_, value) in trend.iter() {
+            let value = value.clamp(0, u32::MAX as i64) as u32;
+            worst = Some(worst.map_or(value, |w| w.min(value)));
+        }
+        worst
+    }
+
+    fn validate_criteria(criteria_hashes: &Vec<BytesN<32>>, min_match: u32) -> Result<(), Error> {
+        if min_match == 0 || min_match > criteria_hashes.len() {
+            return Err(Error::InvalidInput);
+        }
+        Ok(())
+    }
+
+    fn store_reminder(
+        env: &Env,
+        patient_id: Address,
+        provider_id: Address,
+        reminder_type: Symbol,
+        due_date: u64,
+        priority: Symbol,
+        recurrence_days: Option<u32>,
+    ) -> Result<u64, Error> {
+        // Per-patient index so multiple reminders can coexist. A full index
+        // sheds completed reminders first; their records stay retrievable.
+        let index_key = DataKey::PatientReminders(patient_id.clone());
+        let mut ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&index_key)
+            .unwrap_or(Vec::new(env));
+        if ids.len() >= MAX_PATIENT_REMINDERS {
+            let mut open = Vec::new(env);
+            for id in ids.iter() {
+                let reminder: Option<Reminder> =
+                    env.storage().persistent().get(&DataKey::Reminder(id));
+                if reminder.is_some_and(|r| r.completed_at.is_none()) {
+                    open.push_back(id);
+                }
+            }
+            if open.len() >= MAX_PATIENT_REMINDERS {
+                return Err(Error::ReminderLimitReached);
+            }
+            ids = open;
+        }
+
+        let reminder_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ReminderCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .persistent()
+            .set(&DataKey::ReminderCounter, &reminder_id);
+
+        let reminder = Reminder {
+            reminder_id,
+            patient_id: patient_id.clone(),
+            provider_id,
+            reminder_type,
+            due_date,
+            priority,
+            recurrence_days,
+            created_at: env.ledger().timestamp(),
+            completed_at: None,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Reminder(reminder_id), &reminder);
+
+        ReminderCreated {
+            patient_id: reminder.patient_id,
+            reminder_id,
+            reminder_type: reminder.reminder_type,
+            due_date: reminder.due_date,
+        }
+        .publish(env);
+
+        ids.push_back(reminder_id);
+        env.storage().persistent().set(&index_key, &ids);
+
+        Ok(reminder_id)
+    }
+
+    fn priority_rank(env: &Env, priority: &Symbol) -> Option<u32> {
+        if *priority == Symbol::new(env, "high") {
+            Some(0)
+        } else if *priority == Symbol::new(env, "medium") {
+            Some(1)
+        } else if *priority == Symbol::new(env, "low") {
+            Some(2)
+        } else {
+            None
+        }
+    }
+
+    fn reminder_sort_key(env: &Env, reminder: &Reminder) -> (u32, u64) {
+        let rank = Self::priority_rank(env, &reminder.priority).unwrap_or(u32::MAX);
+        (rank, reminder.due_date)
+    }
+
+    fn interaction_key(drug_a: String, drug_b: String) -> DataKey {
+        // (A, B) and (B, A) share one registry entry
+        if drug_a <= drug_b {
+            DataKey::Interaction(drug_a, drug_b)
+        } else {
+            DataKey::Interaction(drug_b, drug_a)
+        }
+    }
+
+    fn push_unique(alerts: &mut Vec<Symbol>, alert: Symbol) {
+        if !alerts.contains(&alert) {
+            alerts.push_back(alert);
+        }
+    }
+
+    fn format_mg(env: &Env, value: u64) -> String {
+        // Render "<value>mg" without alloc
+        let mut buf = [0u8; 22];
+        let mut start = 20;
+        let mut v = value;
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (v % 10) as u8;
+            v /= 10;
+            if v == 0 {
+                break;
+            }
+        }
+        buf[20] = b'm';
+        buf[21] = b'g';
+        String::from_bytes(env, &buf[start..])
+    }
 }
 
 mod test;
@@ -1,11 +1,15 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{Address, BytesN, Env, String, Symbol, Vec, testutils::Address as _};
+use soroban_sdk::{
+    Address, BytesN, Env, Map, String, Symbol, TryFromVal, Vec,
+    testutils::{Address as _, Events, Ledger},
+    vec,
+};
 
 #[test]
 fn test_register_and_evaluate_guideline() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ClinicalGuidelineContract);
+    let contract_id = env.register(ClinicalGuidelineContract, ());
     let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -14,13 +18,22 @@ fn test_register_and_evaluate_guideline() {
 
     // Register guideline (Mocking auth)
     env.mock_all_auths();
+    client.initialize(&admin);
+    let mut alternatives = Vec::new(&env);
+    alternatives.push_back(String::from_str(&env, "Antiviral if high risk"));
     client.register_clinical_guideline(
         &admin,
-        &guideline_id,
-        &String::from_str(&env, "Flu"),
-        &criteria_hash,
-        &criteria_hash,
-        &Symbol::new(&env, "A"),
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Flu"),
+            criteria_hashes: vec![&env, criteria_hash.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: criteria_hash.clone(),
+            recommendation: String::from_str(&env, "Annual influenza vaccination"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: alternatives.clone(),
+        },
     );
 
     // Evaluate: Match
@@ -28,9 +41,19 @@ fn test_register_and_evaluate_guideline() {
         &Address::generate(&env),
         &Address::generate(&env),
         &guideline_id,
-        &criteria_hash,
+        &vec![&env, criteria_hash.clone()],
+        &false,
+        &false,
     );
     assert!(result.applicable);
+    assert_eq!(result.matched_count, 1);
+    assert_eq!(
+        result.recommendation,
+        String::from_str(&env, "Annual influenza vaccination")
+    );
+    assert_eq!(result.strength, RecommendationStrength::Strong);
+    assert_eq!(result.evidence_level, EvidenceLevel::A);
+    assert_eq!(result.alternative_options, alternatives);
 
     // Evaluate: No Match (different hash)
     let wrong_hash = BytesN::from_array(&env, &[1u8; 32]);
@@ -38,17 +61,261 @@ fn test_register_and_evaluate_guideline() {
         &Address::generate(&env),
         &Address::generate(&env),
         &guideline_id,
-        &wrong_hash,
+        &vec![&env, wrong_hash],
+        &false,
+        &false,
     );
     assert!(!result_fail.applicable);
 }
 
+#[test]
+fn test_guideline_versioning_and_deprecation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let guideline_id = String::from_str(&env, "HTN");
+    let v1_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let v2_hash = BytesN::from_array(&env, &[2u8; 32]);
+
+    client.register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Hypertension"),
+            criteria_hashes: vec![&env, v1_hash.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: v1_hash.clone(),
+            recommendation: String::from_str(&env, "Lifestyle changes"),
+            strength: RecommendationStrength::Moderate,
+            evidence_level: EvidenceLevel::B,
+            alternative_options: Vec::new(&env),
+        },
+    );
+
+    let version = client.update_guideline(
+        &admin,
+        &guideline_id,
+        &vec![&env, v2_hash.clone()],
+        &1,
+        &v2_hash,
+        &EvidenceLevel::A,
+    );
+    assert_eq!(version, 2);
+
+    let v1 = client.get_guideline_version(&guideline_id, &1);
+    assert_eq!(v1.criteria_hashes, vec![&env, v1_hash]);
+    assert_eq!(v1.evidence_level, EvidenceLevel::B);
+    let v2 = client.get_guideline_version(&guideline_id, &2);
+    assert_eq!(v2.criteria_hashes, vec![&env, v2_hash.clone()]);
+
+    let current = client.get_guideline(&guideline_id);
+    assert_eq!(current, v2);
+    assert_eq!(current.version, 2);
+    assert_eq!(
+        client.try_get_guideline(&String::from_str(&env, "MISSING")),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+
+    let patient = Address::generate(&env);
+    let attributes = vec![&env, v2_hash];
+    assert!(
+        client
+            .evaluate_guideline(&patient, &admin, &guideline_id, &attributes, &false, &false)
+            .applicable
+    );
+
+    let reason = String::from_str(&env, "Superseded by HTN-2025");
+    client.deprecate_guideline(&admin, &guideline_id, &reason);
+    let result =
+        client.evaluate_guideline(&patient, &admin, &guideline_id, &attributes, &false, &false);
+    assert!(!result.applicable);
+    assert_eq!(result.reason, Some(reason));
+}
+
+#[test]
+fn test_multi_criteria_partial_match() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let guideline_id = String::from_str(&env, "DM2");
+    let mut criteria = Vec::new(&env);
+    for i in 1..=5u8 {
+        criteria.push_back(BytesN::from_array(&env, &[i; 32]));
+    }
+
+    client.register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Type 2 diabetes"),
+            criteria_hashes: criteria.clone(),
+            min_criteria_match: 4,
+            recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+            recommendation: String::from_str(&env, "Start metformin"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: Vec::new(&env),
+        },
+    );
+
+    // Four of five criteria present, plus an unrelated attribute
+    let mut attributes = Vec::new(&env);
+    for i in [1u8, 2, 3, 5, 42] {
+        attributes.push_back(BytesN::from_array(&env, &[i; 32]));
+    }
+    let patient = Address::generate(&env);
+    let result =
+        client.evaluate_guideline(&patient, &admin, &guideline_id, &attributes, &false, &false);
+    assert!(result.applicable);
+    assert_eq!(result.matched_count, 4);
+
+    attributes.remove(0);
+    let result =
+        client.evaluate_guideline(&patient, &admin, &guideline_id, &attributes, &false, &false);
+    assert!(!result.applicable);
+    assert_eq!(result.matched_count, 3);
+
+    // Threshold above the number of criteria is rejected
+    let result = client.try_register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: String::from_str(&env, "BAD"),
+            condition: String::from_str(&env, "NA"),
+            criteria_hashes: criteria.clone(),
+            min_criteria_match: 6,
+            recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+            recommendation: String::from_str(&env, "NA"),
+            strength: RecommendationStrength::Weak,
+            evidence_level: EvidenceLevel::C,
+            alternative_options: Vec::new(&env),
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+}
+
+#[test]
+fn test_evaluate_unknown_guideline_fails() {
+    let env = Env::default();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let result = client.try_evaluate_guideline(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &String::from_str(&env, "MISSING"),
+        &Vec::new(&env),
+        &false,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(Error::GuidelineNotFound)));
+}
+
+fn amoxicillin_protocol(env: &Env) -> DosageProtocol {
+    DosageProtocol {
+        medication: String::from_str(env, "Amoxicillin"),
+        adult_mg_per_kg_x1000: 10_000,     // 10 mg/kg
+        pediatric_mg_per_kg_x1000: 20_000, // 20 mg/kg
+        adult_min_age: 18,
+        min_age: 0,
+        max_dose_mg: 1_000,
+        renal_moderate_pct: 75,
+        renal_severe_pct: 50,
+        frequency: String::from_str(env, "TID"),
+        route: Symbol::new(env, "Oral"),
+        duration: Some(7),
+    }
+}
+
+#[test]
+fn test_recommend_treatment_with_dosage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let guideline_id = String::from_str(&env, "OTITIS-2024");
+    let criterion = BytesN::from_array(&env, &[5u8; 32]);
+    client.register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Acute otitis media"),
+            criteria_hashes: vec![&env, criterion.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+            recommendation: String::from_str(&env, "Start amoxicillin"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: Vec::new(&env),
+        },
+    );
+
+    let patient = Address::generate(&env);
+    let attributes = vec![&env, criterion];
+
+    // No medication named yet, so only the recommendation comes back
+    let (rec, dosage) = client.recommend_treatment(
+        &patient,
+        &admin,
+        &guideline_id,
+        &attributes,
+        &20_000,
+        &6,
+        &None,
+    );
+    assert!(rec.applicable);
+    assert!(dosage.is_none());
+
+    let medication = Some(String::from_str(&env, "Amoxicillin"));
+    client.set_guideline_medication(&admin, &guideline_id, &medication);
+    let (_, dosage) = client.recommend_treatment(
+        &patient,
+        &admin,
+        &guideline_id,
+        &attributes,
+        &20_000,
+        &6,
+        &None,
+    );
+    assert_eq!(dosage.unwrap().dose_mg, 400);
+
+    // A guideline that does not apply yields no dose
+    let (rec, dosage) = client.recommend_treatment(
+        &patient,
+        &admin,
+        &guideline_id,
+        &Vec::new(&env),
+        &20_000,
+        &6,
+        &None,
+    );
+    assert!(!rec.applicable);
+    assert!(dosage.is_none());
+}
+
 #[test]
 fn test_drug_dosage_calculation() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ClinicalGuidelineContract);
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
     let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
 
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
     let weight_grams = 70000; // 70kg
     let result = client.calculate_drug_dosage(
         &Address::generate(&env),
@@ -60,12 +327,290 @@ fn test_drug_dosage_calculation() {
 
     assert_eq!(result.renal_adjustment, true);
     assert_eq!(result.medication, String::from_str(&env, "Amoxicillin"));
+    // 700mg reduced to 75% for GFR 30-59
+    assert_eq!(result.recommended_dose, String::from_str(&env, "525mg"));
+    assert_eq!(result.dose_mg, 525);
+    assert!(!result.capped);
+    assert_eq!(
+        result.monitoring_required.get(0).unwrap(),
+        String::from_str(&env, "Serum_Creatinine")
+    );
+    assert_eq!(result.frequency, String::from_str(&env, "TID"));
+}
+
+#[test]
+fn test_dosage_uses_worst_recent_gfr() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+    let vitals_id = env.register(patient_vitals::PatientVitalsContract, ());
+    let vitals = patient_vitals::PatientVitalsContractClient::new(&env, &vitals_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+    let medication = String::from_str(&env, "Amoxicillin");
+
+    let day = 24 * 60 * 60;
+    env.ledger().set_timestamp(40 * day);
+    // Outside the lookback window, then impaired, then a transient improvement
+    vitals.record_gfr(&patient, &patient, &(5 * day), &20);
+    vitals.record_gfr(&patient, &patient, &(30 * day), &25);
+    vitals.record_gfr(&patient, &patient, &(39 * day), &80);
+
+    // No vitals link: the passed value is used as-is
+    let result = client.calculate_drug_dosage(&patient, &medication, &70_000, &30, &Some(90));
+    assert!(!result.renal_adjustment);
+    assert_eq!(result.dose_mg, 700);
+
+    let outsider = Address::generate(&env);
+    let result = client.try_set_vitals_contract(&outsider, &vitals_id);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.set_vitals_contract(&admin, &vitals_id);
+    let result = client.calculate_drug_dosage(&patient, &medication, &70_000, &30, &Some(90));
+    assert!(result.renal_adjustment);
+    // GFR 25 in the window is severe: 700mg reduced to 50%
+    assert_eq!(result.dose_mg, 350);
+
+    // Patients without readings fall back to the passed value
+    let other = Address::generate(&env);
+    let result = client.calculate_drug_dosage(&other, &medication, &70_000, &30, &Some(45));
+    assert_eq!(result.dose_mg, 525);
+}
+
+#[test]
+fn test_pediatric_dosage_band() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let result = client.calculate_drug_dosage(
+        &Address::generate(&env),
+        &String::from_str(&env, "Amoxicillin"),
+        &20_000, // 20kg
+        &6,
+        &None,
+    );
+    assert_eq!(result.recommended_dose, String::from_str(&env, "400mg"));
+    assert_eq!(result.dose_mg, 400);
+    assert!(!result.renal_adjustment);
+    assert_eq!(result.monitoring_required.len(), 0);
+}
+
+#[test]
+fn test_severe_renal_impairment_halves_dose() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let result = client.calculate_drug_dosage(
+        &Address::generate(&env),
+        &String::from_str(&env, "Amoxicillin"),
+        &70_000,
+        &40,
+        &Some(25),
+    );
+    assert!(result.renal_adjustment);
+    assert_eq!(result.recommended_dose, String::from_str(&env, "350mg"));
+}
+
+#[test]
+fn test_dose_capped_at_protocol_maximum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let patient = Address::generate(&env);
+    let medication = String::from_str(&env, "Amoxicillin");
+
+    // 250kg would compute 2500mg, clamped to the 1000mg maximum
+    let result = client.calculate_drug_dosage(&patient, &medication, &250_000, &40, &None);
+    assert!(result.capped);
+    assert_eq!(result.dose_mg, 1_000);
+    assert_eq!(result.recommended_dose, String::from_str(&env, "1000mg"));
+
+    let result = client.try_calculate_drug_dosage(&patient, &medication, &0, &40, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+}
+
+#[test]
+fn test_dosage_rejects_implausible_weights() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let patient = Address::generate(&env);
+    let medication = String::from_str(&env, "Amoxicillin");
+
+    // 70 looks like kilograms passed where grams were expected
+    for weight in [70u64, 499, 500_001] {
+        let result = client.try_calculate_drug_dosage(&patient, &medication, &weight, &40, &None);
+        assert_eq!(result, Err(Ok(Error::InvalidInput)));
+    }
+    let result = client.calculate_drug_dosage(&patient, &medication, &500, &0, &None);
+    assert_eq!(result.dose_mg, 10);
+}
+
+#[test]
+fn test_dosage_contraindicated_below_min_age() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let mut protocol = amoxicillin_protocol(&env);
+    protocol.medication = String::from_str(&env, "Doxycycline");
+    protocol.min_age = 8;
+    client.register_dosage_protocol(&admin, &protocol);
+
+    let patient = Address::generate(&env);
+    let result =
+        client.try_calculate_drug_dosage(&patient, &protocol.medication, &20_000, &6, &None);
+    assert_eq!(result, Err(Ok(Error::Contraindicated)));
+
+    let result = client.calculate_drug_dosage(&patient, &protocol.medication, &30_000, &8, &None);
+    assert_eq!(result.dose_mg, 600);
+}
+
+#[test]
+fn test_dosage_unregistered_medication_fails() {
+    let env = Env::default();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let result = client.try_calculate_drug_dosage(
+        &Address::generate(&env),
+        &String::from_str(&env, "Unknownol"),
+        &70_000,
+        &30,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::GuidelineNotFound)));
+}
+
+#[test]
+fn test_drug_interactions_order_independent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let warfarin = String::from_str(&env, "Warfarin");
+    let aspirin = String::from_str(&env, "Aspirin");
+    let metformin = String::from_str(&env, "Metformin");
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_interaction(
+        &admin,
+        &warfarin,
+        &aspirin,
+        &Symbol::new(&env, "major"),
+    );
+
+    let mut regimen = Vec::new(&env);
+    regimen.push_back(aspirin.clone());
+    regimen.push_back(metformin.clone());
+    regimen.push_back(warfarin.clone());
+
+    let found = client.check_interactions(&regimen);
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        found.get(0).unwrap(),
+        (aspirin, warfarin, Symbol::new(&env, "major"))
+    );
+
+    let mut safe = Vec::new(&env);
+    safe.push_back(metformin);
+    assert_eq!(client.check_interactions(&safe).len(), 0);
+}
+
+#[test]
+fn test_weighted_risk_score() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let calculator = Symbol::new(&env, "CHA2DS2");
+    let mut weights = Vec::new(&env);
+    weights.push_back(1);
+    weights.push_back(2);
+    weights.push_back(3);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let mut names = vec![
+        &env,
+        Symbol::new(&env, "chf"),
+        Symbol::new(&env, "age"),
+        Symbol::new(&env, "stroke"),
+    ];
+    client.register_risk_calculator(&admin, &calculator, &weights, &names, &3, &6);
+
+    let patient = Address::generate(&env);
+    let mut inputs = Vec::new(&env);
+    inputs.push_back(1);
+    inputs.push_back(0);
+    inputs.push_back(1);
+    // 1*1 + 0*2 + 1*3 = 4
+    let (score, category) = client.assess_risk_score(&patient, &calculator, &inputs, &None);
+    assert_eq!(score, 4);
+    assert_eq!(category, Symbol::new(&env, "moderate"));
+
+    inputs.set(1, 2);
+    let (score, category) = client.assess_risk_score(&patient, &calculator, &inputs, &None);
+    assert_eq!(score, 8);
+    assert_eq!(category, Symbol::new(&env, "high"));
+
+    let result = client.assess_risk(&patient, &calculator, &inputs, &None);
+    assert_eq!(result.score, 8);
+    assert_eq!(result.category, Symbol::new(&env, "high"));
+    assert_eq!(result.components.len(), 3);
+    assert_eq!(result.components.get(1).unwrap(), (Symbol::new(&env, "age"), 4));
+    assert_eq!(result.components.get(2).unwrap(), (Symbol::new(&env, "stroke"), 3));
+
+    inputs.pop_back();
+    assert_eq!(
+        client.try_assess_risk_score(&patient, &calculator, &inputs, &None),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    // Names must line up with weights
+    names.pop_back();
+    assert_eq!(
+        client.try_register_risk_calculator(&admin, &calculator, &weights, &names, &3, &6),
+        Err(Ok(Error::InvalidInput))
+    );
 }
 
 #[test]
 fn test_preventive_care_logic() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ClinicalGuidelineContract);
+    let contract_id = env.register(ClinicalGuidelineContract, ());
     let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
 
     // Test for older patient
@@ -80,23 +625,915 @@ fn test_preventive_care_logic() {
     assert!(alerts.len() >= 2);
 }
 
+#[test]
+fn test_preventive_care_gender_and_risk_factors() {
+    let env = Env::default();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let female = client.check_preventive_care(
+        &Address::generate(&env),
+        &42,
+        &Symbol::new(&env, "F"),
+        &Vec::new(&env),
+    );
+    assert!(female.contains(Symbol::new(&env, "Mammography")));
+    assert!(!female.contains(Symbol::new(&env, "AAA_Screening")));
+
+    let mut risk_factors = Vec::new(&env);
+    risk_factors.push_back(Symbol::new(&env, "smoker"));
+    risk_factors.push_back(Symbol::new(&env, "diabetes"));
+    risk_factors.push_back(Symbol::new(&env, "hypertension"));
+    let male = client.check_preventive_care(
+        &Address::generate(&env),
+        &70,
+        &Symbol::new(&env, "M"),
+        &risk_factors,
+    );
+    assert!(male.contains(Symbol::new(&env, "AAA_Screening")));
+    assert!(male.contains(Symbol::new(&env, "Eye_Exam")));
+    assert!(!male.contains(Symbol::new(&env, "Mammography")));
+
+    // Hypertension re-triggers the age-based BP check; it must appear once
+    let bp = Symbol::new(&env, "Blood_Pressure_Check");
+    assert_eq!(male.iter().filter(|a| *a == bp).count(), 1);
+}
+
+#[test]
+fn test_multiple_reminders_per_patient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    let first = client.create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &Symbol::new(&env, "Flu_Shot"),
+        &1000,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    let second = client.create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &Symbol::new(&env, "Eye_Exam"),
+        &2000,
+        &Symbol::new(&env, "low"),
+        &None,
+    );
+    assert_ne!(first, second);
+
+    let reminders = client.get_reminders(&patient);
+    assert_eq!(reminders.len(), 2);
+    assert_eq!(reminders.get(0).unwrap().due_date, 1000);
+    assert_eq!(reminders.get(1).unwrap().due_date, 2000);
+    assert_eq!(client.get_reminders(&Address::generate(&env)).len(), 0);
+}
+
+#[test]
+fn test_reminders_sorted_by_priority_then_due_date() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let kind = Symbol::new(&env, "Checkup");
+    let (high, medium, low) = (
+        Symbol::new(&env, "high"),
+        Symbol::new(&env, "medium"),
+        Symbol::new(&env, "low"),
+    );
+    let low_id = client.create_reminder(&provider, &patient, &provider, &kind, &100, &low, &None);
+    let late_high =
+        client.create_reminder(&provider, &patient, &provider, &kind, &900, &high, &None);
+    let medium_id =
+        client.create_reminder(&provider, &patient, &provider, &kind, &50, &medium, &None);
+    let early_high =
+        client.create_reminder(&provider, &patient, &provider, &kind, &300, &high, &None);
+
+    let sorted = client.list_reminders_sorted(&patient);
+    assert_eq!(sorted.len(), 4);
+    assert_eq!(sorted.get(0).unwrap().reminder_id, early_high);
+    assert_eq!(sorted.get(1).unwrap().reminder_id, late_high);
+    assert_eq!(sorted.get(2).unwrap().reminder_id, medium_id);
+    assert_eq!(sorted.get(3).unwrap().reminder_id, low_id);
+    assert_eq!(sorted.get(2).unwrap().priority, medium);
+
+    let urgent = Symbol::new(&env, "urgent");
+    let result =
+        client.try_create_reminder(&provider, &patient, &provider, &kind, &100, &urgent, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+}
+
+#[test]
+fn test_complete_and_list_due_reminders() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let flu = client.create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &Symbol::new(&env, "Flu_Shot"),
+        &1000,
+        &Symbol::new(&env, "high"),
+        &None,
+    );
+    client.create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &Symbol::new(&env, "Eye_Exam"),
+        &5000,
+        &Symbol::new(&env, "low"),
+        &None,
+    );
+
+    assert_eq!(client.list_due_reminders(&patient, &2000).len(), 1);
+    assert_eq!(client.list_due_reminders(&patient, &6000).len(), 2);
+
+    client.complete_reminder(&flu, &patient);
+    let due = client.list_due_reminders(&patient, &6000);
+    assert_eq!(due.len(), 1);
+    assert_eq!(
+        due.get(0).unwrap().reminder_type,
+        Symbol::new(&env, "Eye_Exam")
+    );
+
+    assert_eq!(
+        client.try_complete_reminder(&flu, &patient),
+        Err(Ok(Error::ReminderAlreadyCompleted))
+    );
+    assert_eq!(
+        client.try_complete_reminder(&flu, &Address::generate(&env)),
+        Err(Ok(Error::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_recurring_reminder_rolls_forward() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let screening = Symbol::new(&env, "Mammography");
+    let priority = Symbol::new(&env, "medium");
+    let yearly = Some(365);
+    let first = client.create_reminder(
+        &provider, &patient, &provider, &screening, &1000, &priority, &yearly,
+    );
+
+    let next = client.complete_reminder(&first, &patient).unwrap();
+    let reminders = client.get_reminders(&patient);
+    assert_eq!(reminders.len(), 2);
+    let follow_up = reminders.get(1).unwrap();
+    assert_eq!(follow_up.reminder_id, next);
+    assert_eq!(follow_up.due_date, 1000 + 365 * 86400);
+    assert_eq!(follow_up.reminder_type, screening);
+    assert_eq!(follow_up.recurrence_days, Some(365));
+    assert!(follow_up.completed_at.is_none());
+
+    // One-off reminders do not reschedule
+    let once = client.create_reminder(
+        &provider, &patient, &provider, &screening, &1000, &priority, &None,
+    );
+    assert_eq!(client.complete_reminder(&once, &patient), None);
+
+    let result = client.try_create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &screening,
+        &1000,
+        &priority,
+        &Some(0),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+
+    // A roll-forward past the end of time is rejected and the completion undone
+    let far = u64::MAX;
+    let last = client.create_reminder(
+        &provider, &patient, &provider, &screening, &far, &priority, &yearly,
+    );
+    let result = client.try_complete_reminder(&last, &patient);
+    assert_eq!(result, Err(Ok(Error::InvalidInput)));
+    let reminders = client.get_reminders(&patient);
+    let last = reminders.iter().find(|r| r.reminder_id == last).unwrap();
+    assert!(last.completed_at.is_none());
+}
+
+#[test]
+fn test_reminder_creation_is_gated_and_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let kind = Symbol::new(&env, "Checkup");
+    let priority = Symbol::new(&env, "low");
+
+    let outsider = Address::generate(&env);
+    let result = client.try_create_reminder(
+        &outsider, &patient, &provider, &kind, &100, &priority, &None,
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Patients may schedule their own reminders
+    let first =
+        client.create_reminder(&patient, &patient, &provider, &kind, &100, &priority, &None);
+    for _ in 1..100 {
+        client.create_reminder(
+            &provider, &patient, &provider, &kind, &100, &priority, &None,
+        );
+    }
+    let result = client.try_create_reminder(
+        &provider, &patient, &provider, &kind, &100, &priority, &None,
+    );
+    assert_eq!(result, Err(Ok(Error::ReminderLimitReached)));
+
+    // Completing one frees a slot; the completed reminder leaves the index
+    client.complete_reminder(&first, &patient);
+    client.create_reminder(
+        &provider, &patient, &provider, &kind, &100, &priority, &None,
+    );
+    let reminders = client.get_reminders(&patient);
+    assert_eq!(reminders.len(), 100);
+    assert!(reminders.iter().all(|r| r.reminder_id != first));
+}
+
+#[test]
+fn test_registered_care_pathway() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let condition = String::from_str(&env, "Diabetes");
+    let mut steps = Vec::new(&env);
+    steps.push_back(String::from_str(&env, "HbA1c Test"));
+    steps.push_back(String::from_str(&env, "Start Metformin"));
+    steps.push_back(String::from_str(&env, "Dietitian Referral"));
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_care_pathway(&admin, &condition, &steps);
+
+    let mut done = Vec::new(&env);
+    done.push_back(String::from_str(&env, "HbA1c Test"));
+    let pathway = client.suggest_care_pathway(&Address::generate(&env), &condition, &done);
+    assert_eq!(pathway.steps.len(), 2);
+    assert_eq!(
+        pathway.steps.get(0).unwrap(),
+        String::from_str(&env, "Start Metformin")
+    );
+
+    // Unregistered conditions fall back to the generic pathway
+    let generic = client.suggest_care_pathway(
+        &Address::generate(&env),
+        &String::from_str(&env, "Asthma"),
+        &Vec::new(&env),
+    );
+    assert_eq!(generic.steps.len(), 2);
+    assert_eq!(
+        generic.steps.get(0).unwrap(),
+        String::from_str(&env, "Initial Assessment")
+    );
+}
+
+#[test]
+fn test_advance_care_pathway() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let condition = String::from_str(&env, "COPD");
+    let steps = vec![
+        &env,
+        String::from_str(&env, "Spirometry"),
+        String::from_str(&env, "Inhaler Training"),
+    ];
+    client.register_care_pathway(&admin, &condition, &steps);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    assert_eq!(client.get_pathway_progress(&patient, &condition), 0);
+    assert_eq!(client.advance_pathway(&patient, &provider, &condition), 1);
+    assert_eq!(client.advance_pathway(&patient, &provider, &condition), 2);
+    assert_eq!(client.get_pathway_progress(&patient, &condition), 2);
+
+    // No step left to advance to
+    assert_eq!(
+        client.try_advance_pathway(&patient, &provider, &condition),
+        Err(Ok(Error::InvalidInput))
+    );
+    // Progress is tracked per patient
+    let other = Address::generate(&env);
+    assert_eq!(client.get_pathway_progress(&other, &condition), 0);
+    assert_eq!(
+        client.try_advance_pathway(&patient, &provider, &String::from_str(&env, "Asthma")),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+}
+
 #[test]
 #[should_panic(expected = "HostError: Error(Auth, InvalidAction)")]
 fn test_unauthorized_registration() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ClinicalGuidelineContract);
+    let contract_id = env.register(ClinicalGuidelineContract, ());
     let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    client.mock_all_auths().initialize(&admin);
 
     // We are NOT calling env.mock_all_auths()
     // This will trigger the Host's built-in Auth panic
     client.register_clinical_guideline(
         &admin,
-        &String::from_str(&env, "FAIL"),
-        &String::from_str(&env, "NA"),
-        &BytesN::from_array(&env, &[0u8; 32]),
-        &BytesN::from_array(&env, &[0u8; 32]),
-        &Symbol::new(&env, "B"),
+        &GuidelineInput {
+            guideline_id: String::from_str(&env, "FAIL"),
+            condition: String::from_str(&env, "NA"),
+            criteria_hashes: vec![&env, BytesN::from_array(&env, &[0u8; 32])],
+            min_criteria_match: 1,
+            recommendation_hash: BytesN::from_array(&env, &[0u8; 32]),
+            recommendation: String::from_str(&env, "NA"),
+            strength: RecommendationStrength::Weak,
+            evidence_level: EvidenceLevel::B,
+            alternative_options: Vec::new(&env),
+        },
+    );
+}
+
+#[test]
+fn test_non_admin_registration_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    client.initialize(&Address::generate(&env));
+
+    let result = client.try_register_clinical_guideline(
+        &Address::generate(&env),
+        &GuidelineInput {
+            guideline_id: String::from_str(&env, "ROGUE"),
+            condition: String::from_str(&env, "NA"),
+            criteria_hashes: vec![&env, BytesN::from_array(&env, &[0u8; 32])],
+            min_criteria_match: 1,
+            recommendation_hash: BytesN::from_array(&env, &[0u8; 32]),
+            recommendation: String::from_str(&env, "NA"),
+            strength: RecommendationStrength::Weak,
+            evidence_level: EvidenceLevel::B,
+            alternative_options: Vec::new(&env),
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_evaluation_logging() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let guideline_id = String::from_str(&env, "CKD-2024");
+    let criterion = BytesN::from_array(&env, &[3u8; 32]);
+    client.register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Chronic kidney disease"),
+            criteria_hashes: vec![&env, criterion.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+            recommendation: String::from_str(&env, "Start ACE inhibitor"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: Vec::new(&env),
+        },
+    );
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let attributes = vec![&env, criterion];
+
+    client.evaluate_guideline(
+        &patient,
+        &provider,
+        &guideline_id,
+        &attributes,
+        &false,
+        &false,
+    );
+    assert_eq!(client.get_eval_history(&patient).len(), 0);
+
+    client.evaluate_guideline(
+        &patient,
+        &provider,
+        &guideline_id,
+        &attributes,
+        &true,
+        &false,
+    );
+    client.evaluate_guideline(
+        &patient,
+        &provider,
+        &guideline_id,
+        &Vec::new(&env),
+        &true,
+        &false,
+    );
+
+    let history = client.get_eval_history(&patient);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().guideline_id, guideline_id);
+    assert_eq!(history.get(0).unwrap().provider_id, provider);
+    assert!(history.get(0).unwrap().applicable);
+    assert!(!history.get(1).unwrap().applicable);
+}
+
+#[test]
+fn test_evaluation_cache() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let guideline_id = String::from_str(&env, "CKD-2024");
+    let criterion = BytesN::from_array(&env, &[3u8; 32]);
+    client.register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Chronic kidney disease"),
+            criteria_hashes: vec![&env, criterion.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+            recommendation: String::from_str(&env, "Start ACE inhibitor"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: Vec::new(&env),
+        },
+    );
+
+    let patient = Address::generate(&env);
+    let attributes = vec![&env, criterion.clone()];
+    let evaluate = |bypass: bool| {
+        client.evaluate_guideline(
+            &patient,
+            &admin,
+            &guideline_id,
+            &attributes,
+            &false,
+            &bypass,
+        )
+    };
+    let fresh = evaluate(false);
+    assert!(fresh.applicable);
+
+    // Tamper with the cached entry to see which path answers
+    let key = DataKey::EvalCache(
+        guideline_id.clone(),
+        env.crypto()
+            .sha256(&Bytes::from_array(&env, &criterion.to_array()))
+            .to_bytes(),
+    );
+    env.as_contract(&contract_id, || {
+        let mut entry: CachedEvaluation = env.storage().temporary().get(&key).unwrap();
+        entry.recommendation.matched_count = 42;
+        env.storage().temporary().set(&key, &entry);
+    });
+    assert_eq!(evaluate(false).matched_count, 42);
+    assert_eq!(evaluate(true), fresh);
+    assert_eq!(evaluate(false), fresh);
+
+    // A new guideline version ignores entries cached for the old one
+    let other = BytesN::from_array(&env, &[4u8; 32]);
+    client.update_guideline(
+        &admin,
+        &guideline_id,
+        &vec![&env, other.clone()],
+        &1,
+        &other,
+        &EvidenceLevel::A,
+    );
+    assert!(!evaluate(false).applicable);
+}
+
+#[test]
+fn test_evaluation_cache_survives_reregistration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+    let care_plan_id = env.register(care_plan::CarePlanContract, ());
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_care_plan_contract(&admin, &care_plan_id);
+    let guideline_id = String::from_str(&env, "CKD-2024");
+    let register = |criterion: &BytesN<32>| {
+        client.register_clinical_guideline(
+            &admin,
+            &GuidelineInput {
+                guideline_id: guideline_id.clone(),
+                condition: String::from_str(&env, "Chronic kidney disease"),
+                criteria_hashes: vec![&env, criterion.clone()],
+                min_criteria_match: 1,
+                recommendation_hash: BytesN::from_array(&env, &[9u8; 32]),
+                recommendation: String::from_str(&env, "Start ACE inhibitor"),
+                strength: RecommendationStrength::Strong,
+                evidence_level: EvidenceLevel::A,
+                alternative_options: Vec::new(&env),
+            },
+        )
+    };
+
+    let criterion = BytesN::from_array(&env, &[3u8; 32]);
+    register(&criterion);
+    let patient = Address::generate(&env);
+    let attributes = vec![&env, criterion];
+    let evaluate =
+        || client.evaluate_guideline(&patient, &admin, &guideline_id, &attributes, &false, &false);
+    assert!(evaluate().applicable);
+
+    // Same id, version and timestamp but different criteria: the cached
+    // answer for the removed guideline must not be served
+    client.remove_guideline(&admin, &guideline_id);
+    register(&BytesN::from_array(&env, &[4u8; 32]));
+    let recommendation = evaluate();
+    assert!(!recommendation.applicable);
+    assert_eq!(recommendation.matched_count, 0);
+}
+
+fn pathway_node(
+    env: &Env,
+    step: &str,
+    branches: &[(&str, u32)],
+    default_next: Option<u32>,
+) -> PathwayNode {
+    let mut map = Map::new(env);
+    for (decision, next) in branches {
+        map.set(Symbol::new(env, decision), *next);
+    }
+    PathwayNode {
+        step: String::from_str(env, step),
+        branches: map,
+        default_next,
+    }
+}
+
+#[test]
+fn test_branched_care_pathway() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let condition = String::from_str(&env, "Chest_Pain");
+
+    // Targets must exist within the pathway
+    let broken = vec![&env, pathway_node(&env, "Triage", &[("high", 3)], None)];
+    assert_eq!(
+        client.try_register_branched_pathway(&admin, &condition, &broken),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    let nodes = vec![
+        &env,
+        pathway_node(&env, "Troponin", &[("positive", 1), ("negative", 2)], None),
+        pathway_node(&env, "Cath_Lab", &[], None),
+        pathway_node(&env, "Stress_Test", &[("abnormal", 1)], Some(3)),
+        pathway_node(&env, "Discharge", &[], None),
+    ];
+    client.register_branched_pathway(&admin, &condition, &nodes);
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let negative = Symbol::new(&env, "negative");
+    let normal = Symbol::new(&env, "normal");
+    assert_eq!(client.get_pathway_node(&patient, &condition), 0);
+    assert_eq!(
+        client.next_pathway_step(&patient, &provider, &condition, &negative),
+        String::from_str(&env, "Stress_Test")
+    );
+    // Unmatched decisions follow the default branch
+    assert_eq!(
+        client.next_pathway_step(&patient, &provider, &condition, &normal),
+        String::from_str(&env, "Discharge")
+    );
+    assert_eq!(client.get_pathway_node(&patient, &condition), 3);
+    assert_eq!(
+        client.try_next_pathway_step(&patient, &provider, &condition, &normal),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    // Each patient forks independently
+    let other = Address::generate(&env);
+    let positive = Symbol::new(&env, "positive");
+    assert_eq!(
+        client.next_pathway_step(&other, &provider, &condition, &positive),
+        String::from_str(&env, "Cath_Lab")
+    );
+}
+
+fn register_simple_guideline(
+    env: &Env,
+    client: &ClinicalGuidelineContractClient,
+    admin: &Address,
+    id: &str,
+) -> String {
+    let guideline_id = String::from_str(env, id);
+    let criteria_hash = BytesN::from_array(env, &[3u8; 32]);
+    client.register_clinical_guideline(
+        admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(env, "Hypertension"),
+            criteria_hashes: vec![env, criteria_hash.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: criteria_hash.clone(),
+            recommendation: String::from_str(env, "Start ACE inhibitor"),
+            strength: RecommendationStrength::Strong,
+            evidence_level: EvidenceLevel::A,
+            alternative_options: Vec::new(env),
+        },
+    );
+    guideline_id
+}
+
+#[test]
+fn test_register_existing_guideline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let guideline_id = register_simple_guideline(&env, &client, &admin, "HTN-01");
+    let criteria_hash = BytesN::from_array(&env, &[4u8; 32]);
+    let result = client.try_register_clinical_guideline(
+        &admin,
+        &GuidelineInput {
+            guideline_id: guideline_id.clone(),
+            condition: String::from_str(&env, "Hypertension"),
+            criteria_hashes: vec![&env, criteria_hash.clone()],
+            min_criteria_match: 1,
+            recommendation_hash: criteria_hash.clone(),
+            recommendation: String::from_str(&env, "Start thiazide"),
+            strength: RecommendationStrength::Weak,
+            evidence_level: EvidenceLevel::C,
+            alternative_options: Vec::new(&env),
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::GuidelineAlreadyExists)));
+
+    // The original guideline and its version are untouched
+    let guideline = client.get_guideline(&guideline_id);
+    assert_eq!(guideline.version, 1);
+    assert_eq!(
+        guideline.recommendation,
+        String::from_str(&env, "Start ACE inhibitor")
+    );
+}
+
+#[test]
+fn test_remove_guideline_checks_care_plan_references() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+    let care_plan_id = env.register(care_plan::CarePlanContract, ());
+    let care_plan = care_plan::CarePlanContractClient::new(&env, &care_plan_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let attached = register_simple_guideline(&env, &client, &admin, "HTN-01");
+    let unused = register_simple_guideline(&env, &client, &admin, "HTN-02");
+    client.update_guideline(
+        &admin,
+        &unused,
+        &vec![&env, BytesN::from_array(&env, &[4u8; 32])],
+        &1,
+        &BytesN::from_array(&env, &[4u8; 32]),
+        &EvidenceLevel::B,
+    );
+
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let plan_id = care_plan.create_care_plan(
+        &patient,
+        &provider,
+        &Symbol::new(&env, "chronic_disease"),
+        &vec![&env, String::from_str(&env, "Hypertension")],
+        &vec![&env, String::from_str(&env, "BP below 130/80")],
+        &1_000_000u64,
+        &30u32,
+        &None,
+        &None,
+    );
+    care_plan.initialize(&admin, &false);
+    care_plan.set_guideline_contract(&admin, &contract_id);
+    care_plan.attach_guideline(
+        &plan_id,
+        &provider,
+        &contract_id,
+        &attached,
+        &Vec::new(&env),
+    );
+
+    // References can't be checked until the care plan contract is known
+    assert_eq!(
+        client.try_remove_guideline(&admin, &unused),
+        Err(Ok(Error::CarePlanContractNotSet))
+    );
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_set_care_plan_contract(&outsider, &care_plan_id),
+        Err(Ok(Error::NotAuthorized))
+    );
+    client.set_care_plan_contract(&admin, &care_plan_id);
+
+    assert_eq!(
+        client.try_remove_guideline(&admin, &attached),
+        Err(Ok(Error::GuidelineInUse))
+    );
+    assert!(client.try_get_guideline(&attached).is_ok());
+
+    // Unreferenced guidelines are removed along with their version history
+    client.remove_guideline(&admin, &unused);
+    assert_eq!(
+        client.try_get_guideline(&unused),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+    assert_eq!(
+        client.try_get_guideline_version(&unused, &1),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+    assert_eq!(
+        client.try_remove_guideline(&admin, &unused),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+}
+
+#[test]
+fn test_risk_calculator_versions_are_reproducible() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let calculator = Symbol::new(&env, "CHA2DS2");
+    let names = vec![&env, Symbol::new(&env, "chf"), Symbol::new(&env, "age")];
+    let v1 =
+        client.register_risk_calculator(&admin, &calculator, &vec![&env, 1, 2], &names, &3, &6);
+    // Recalibration doubles the age weight
+    let v2 =
+        client.register_risk_calculator(&admin, &calculator, &vec![&env, 1, 4], &names, &3, &6);
+    assert_eq!((v1, v2), (1, 2));
+
+    let patient = Address::generate(&env);
+    let inputs = vec![&env, 1, 1];
+    let latest = client.assess_risk(&patient, &calculator, &inputs, &None);
+    assert_eq!(latest.score, 5);
+    assert_eq!(latest.version, 2);
+
+    // Scores from the old calibration can still be reproduced
+    let original = client.assess_risk(&patient, &calculator, &inputs, &Some(1));
+    assert_eq!(original.score, 3);
+    assert_eq!(original.version, 1);
+    let (score, category) = client.assess_risk_score(&patient, &calculator, &inputs, &Some(1));
+    assert_eq!((score, category), (3, Symbol::new(&env, "moderate")));
+    assert_eq!(
+        client.get_risk_calculator(&calculator, &Some(1)).weights,
+        vec![&env, 1, 2]
+    );
+
+    assert_eq!(
+        client.try_assess_risk(&patient, &calculator, &inputs, &Some(3)),
+        Err(Ok(Error::GuidelineNotFound))
+    );
+}
+
+#[test]
+fn test_preventive_care_batch() {
+    let env = Env::default();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let male = Symbol::new(&env, "M");
+    let diabetic = vec![&env, Symbol::new(&env, "diabetes")];
+    let patients = vec![
+        &env,
+        (Address::generate(&env), 30, male.clone(), diabetic.clone()),
+        (Address::generate(&env), 10, male.clone(), Vec::new(&env)),
+        (
+            Address::generate(&env),
+            55,
+            Symbol::new(&env, "F"),
+            Vec::new(&env),
+        ),
+    ];
+    let results = client.check_preventive_care_batch(&patients);
+    assert_eq!(results.len(), 3);
+    for (i, (patient, age, gender, risk_factors)) in patients.iter().enumerate() {
+        let single = client.check_preventive_care(&patient, &age, &gender, &risk_factors);
+        assert_eq!(results.get(i as u32).unwrap(), single);
+    }
+    assert!(results.get(1).unwrap().is_empty());
+    let mammography = Symbol::new(&env, "Mammography");
+    assert!(results.get(2).unwrap().contains(mammography));
+
+    let mut panel = Vec::new(&env);
+    for _ in 0..51 {
+        panel.push_back((Address::generate(&env), 40, male.clone(), Vec::new(&env)));
+    }
+    assert_eq!(
+        client.try_check_preventive_care_batch(&panel),
+        Err(Ok(Error::InvalidInput))
+    );
+}
+
+fn has_event(env: &Env, name: &str) -> bool {
+    let topic = Symbol::new(env, name);
+    env.events().all().iter().any(|(_, topics, _)| {
+        topics
+            .get(0)
+            .is_some_and(|t| Symbol::try_from_val(env, &t) == Ok(topic.clone()))
+    })
+}
+
+#[test]
+fn test_clinical_events_are_emitted() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ClinicalGuidelineContract, ());
+    let client = ClinicalGuidelineContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    client.initialize(&admin);
+    client.register_dosage_protocol(&admin, &amoxicillin_protocol(&env));
+
+    let guideline_id = register_simple_guideline(&env, &client, &admin, "HTN-01");
+    assert!(has_event(&env, "guideline_registered"));
+
+    let attributes = Vec::new(&env);
+    client.evaluate_guideline(
+        &patient,
+        &provider,
+        &guideline_id,
+        &attributes,
+        &false,
+        &false,
+    );
+    assert!(has_event(&env, "guideline_evaluated"));
+
+    let medication = String::from_str(&env, "Amoxicillin");
+    client.calculate_drug_dosage(&patient, &medication, &70_000, &30, &None);
+    assert!(has_event(&env, "dosage_calculated"));
+
+    let reminder_type = Symbol::new(&env, "Flu_Shot");
+    let priority = Symbol::new(&env, "high");
+    client.create_reminder(
+        &provider,
+        &patient,
+        &provider,
+        &reminder_type,
+        &5000,
+        &priority,
+        &None,
     );
+    assert!(has_event(&env, "reminder_created"));
 }
@@ -0,0 +1,18 @@
+use soroban_sdk::{contractclient, Address, Env, Symbol, Vec};
+
+// -----------------------------------------------------------------------
+// External contract interfaces
+// -----------------------------------------------------------------------
+
+/// Subset of `CarePlanContract` notified when readings are recorded.
+#[allow(dead_code)]
+#[contractclient(name = "CarePlanClient")]
+pub trait CarePlanInterface {
+    fn record_vital_progress(
+        env: Env,
+        vitals_contract: Address,
+        patient_id: Address,
+        measured_at: u64,
+        readings: Vec<(Symbol, i64)>,
+    ) -> u32;
+}
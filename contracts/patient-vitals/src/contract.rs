@@ -1,6 +1,8 @@
+use crate::clients::CarePlanClient;
 use crate::types::{
-    AlertThresholds, DataKey, DeviceReading, DeviceRegistration, Error, MonitoringParameters,
-    Range, VitalAlert, VitalReading, VitalSigns, VitalStatistics,
+    AbnormalReading, AlertThresholds, CarePlanLink, DataKey, DeviceReading, DeviceRegistration,
+    Error, GfrReading, MonitoringParameters, Range, VitalAlert, VitalOutOfRange, VitalReading,
+    VitalSigns, VitalStatistics, VitalSummary, VitalThreshold,
 };
 use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
 
@@ -8,39 +10,177 @@ use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
 // 1 = Unauthorized
 // 2 = Not Found
 // 3 = Invalid Parameter
+// 4 = Already Initialized
+
+// Metrics understood by `extract_vital_value`
+const VITAL_METRICS: [&str; 8] = [
+    "heart_rate",
+    "bp_systolic",
+    "bp_diastolic",
+    "temperature",
+    "respiratory",
+    "oxygen_sat",
+    "blood_glucose",
+    "weight",
+];
 
 #[contract]
 pub struct PatientVitalsContract;
 
 #[contractimpl]
 impl PatientVitalsContract {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().persistent().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().persistent().set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
+    pub fn set_care_plan_link(
+        env: Env,
+        admin: Address,
+        care_plan: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let link = CarePlanLink { care_plan, enabled };
+        env.storage()
+            .persistent()
+            .set(&DataKey::CarePlanLink, &link);
+        Ok(())
+    }
+
+    pub fn get_care_plan_link(env: Env) -> Option<CarePlanLink> {
+        env.storage().persistent().get(&DataKey::CarePlanLink)
+    }
+
+    pub fn set_provider_authorized(
+        env: Env,
+        admin: Address,
+        provider: Address,
+        authorized: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = DataKey::AuthorizedProvider(provider);
+        if authorized {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    pub fn is_provider_authorized(env: Env, provider: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::AuthorizedProvider(provider))
+    }
+
     pub fn record_vital_signs(
         env: Env,
         patient_id: Address,
         recorder: Address, // patient, provider, or device
         measurement_time: u64,
         vitals: VitalSigns,
-    ) -> Result<u64, Error> {
+    ) -> Result<(u64, bool), Error> {
         recorder.require_auth();
 
-        // Load existing history or create new
-        let key = DataKey::VitalsHistory(patient_id.clone());
-        let mut history: Vec<VitalReading> = env
+        // Returning the inserted index / record id and whether any threshold was breached
+        Ok(Self::store_reading(
+            &env,
+            &patient_id,
+            recorder,
+            measurement_time,
+            vitals,
+        ))
+    }
+
+    pub fn record_gfr(
+        env: Env,
+        patient_id: Address,
+        recorder: Address,
+        measurement_time: u64,
+        gfr: u32,
+    ) -> Result<(), Error> {
+        recorder.require_auth();
+        // GFR drives renal dose reductions, so only trusted recorders may chart it
+        if !Self::is_trusted_recorder(&env, &patient_id, &recorder) {
+            return Err(Error::Unauthorized);
+        }
+        if gfr == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let key = DataKey::GfrHistory(patient_id);
+        let mut history: Vec<GfrReading> = env
             .storage()
             .persistent()
             .get(&key)
             .unwrap_or(Vec::new(&env));
-
-        history.push_back(VitalReading {
+        history.push_back(GfrReading {
             measurement_time,
-            vitals,
+            gfr,
             recorder,
         });
-
         env.storage().persistent().set(&key, &history);
+        Ok(())
+    }
 
-        // Returning the inserted index / record id
-        Ok(history.len() as u64)
+    pub fn set_vital_threshold(
+        env: Env,
+        patient_id: Address,
+        provider_id: Address,
+        metric: Symbol,
+        min: i64,
+        max: i64,
+    ) -> Result<(), Error> {
+        provider_id.require_auth();
+
+        if min > max {
+            return Err(Error::InvalidParameter);
+        }
+
+        let key = DataKey::VitalThreshold(patient_id, metric);
+        let threshold = VitalThreshold {
+            provider_id,
+            min,
+            max,
+        };
+
+        env.storage().persistent().set(&key, &threshold);
+        Ok(())
+    }
+
+    pub fn list_abnormal_readings(
+        env: Env,
+        patient_id: Address,
+        metric: Symbol,
+        start: u32,
+        limit: u32,
+    ) -> Vec<AbnormalReading> {
+        let readings: Vec<AbnormalReading> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AbnormalReadings(patient_id, metric))
+            .unwrap_or(Vec::new(&env));
+
+        let mut page = Vec::new(&env);
+        let end = start.saturating_add(limit).min(readings.len());
+        for i in start..end {
+            page.push_back(readings.get_unchecked(i));
+        }
+        page
+    }
+
+    pub fn get_abnormal_count(env: Env, patient_id: Address, metric: Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AbnormalCount(patient_id, metric))
+            .unwrap_or(0)
     }
 
     pub fn set_monitoring_parameters(
@@ -103,22 +243,15 @@ impl PatientVitalsContract {
             return Err(Error::NotFound); // Device not registered
         }
 
-        let key = DataKey::VitalsHistory(patient_id.clone());
-        let mut history: Vec<VitalReading> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(&env));
-
         for reading in readings.iter() {
-            history.push_back(VitalReading {
-                measurement_time: reading.reading_time,
-                vitals: reading.values,
-                recorder: patient_id.clone(), // or device address
-            });
+            Self::store_reading(
+                &env,
+                &patient_id,
+                patient_id.clone(), // or device address
+                reading.reading_time,
+                reading.values,
+            );
         }
-
-        env.storage().persistent().set(&key, &history);
         Ok(())
     }
 
@@ -178,6 +311,81 @@ impl PatientVitalsContract {
         Ok(trends)
     }
 
+    pub fn get_vital_trend(
+        env: Env,
+        patient_id: Address,
+        metric: Symbol,
+        start: u64,
+        end: u64,
+    ) -> Vec<(u64, i64)> {
+        let mut points: Vec<(u64, u32)> = Vec::new(&env);
+        if metric == Symbol::new(&env, "gfr") {
+            let history: Vec<GfrReading> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::GfrHistory(patient_id.clone()))
+                .unwrap_or(Vec::new(&env));
+            for record in history.iter() {
+                // Skip readings whose recorder has since lost authorization
+                if Self::is_trusted_recorder(&env, &patient_id, &record.recorder) {
+                    points.push_back((record.measurement_time, record.gfr));
+                }
+            }
+        } else {
+            let history: Vec<VitalReading> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::VitalsHistory(patient_id))
+                .unwrap_or(Vec::new(&env));
+            for record in history.iter() {
+                if let Some(val) = Self::extract_vital_value(&env, &record.vitals, &metric) {
+                    points.push_back((record.measurement_time, val));
+                }
+            }
+        }
+
+        // Readings may be recorded out of order, so insert each point sorted by time
+        let mut trend: Vec<(u64, i64)> = Vec::new(&env);
+        for (time, val) in points.iter() {
+            if time < start || time > end {
+                continue;
+            }
+            let mut pos = trend.len();
+            while pos > 0 && trend.get(pos - 1).unwrap().0 > time {
+                pos -= 1;
+            }
+            trend.insert(pos, (time, val as i64));
+        }
+
+        trend
+    }
+
+    pub fn get_latest_vital(
+        env: Env,
+        patient_id: Address,
+        vital_type: Symbol,
+    ) -> Option<(u64, u32)> {
+        // Most recent reading (by measurement time) that carries this metric
+        let key = DataKey::VitalsHistory(patient_id);
+        let history: Vec<VitalReading> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut latest: Option<(u64, u32)> = None;
+        for record in history.iter() {
+            if let Some(val) = Self::extract_vital_value(&env, &record.vitals, &vital_type) {
+                match latest {
+                    Some((time, _)) if time > record.measurement_time => {}
+                    _ => latest = Some((record.measurement_time, val)),
+                }
+            }
+        }
+
+        latest
+    }
+
     pub fn calculate_vital_statistics(
         env: Env,
         patient_id: Address,
@@ -232,6 +440,209 @@ impl PatientVitalsContract {
         })
     }
 
+    pub fn vital_summary(
+        env: Env,
+        patient_id: Address,
+        metric: Symbol,
+        start: u64,
+        end: u64,
+    ) -> VitalSummary {
+        let key = DataKey::VitalsHistory(patient_id);
+        let history: Vec<VitalReading> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut summary = VitalSummary {
+            min: i64::MAX,
+            max: i64::MIN,
+            avg: 0,
+            count: 0,
+        };
+        let mut sum: i64 = 0;
+        for record in history.iter() {
+            if record.measurement_time < start || record.measurement_time > end {
+                continue;
+            }
+            if let Some(val) = Self::extract_vital_value(&env, &record.vitals, &metric) {
+                let val = val as i64;
+                summary.min = summary.min.min(val);
+                summary.max = summary.max.max(val);
+                sum += val;
+                summary.count += 1;
+            }
+        }
+
+        if summary.count == 0 {
+            summary.min = 0;
+            summary.max = 0;
+        } else {
+            // Integer average, truncated toward zero
+            summary.avg = sum / summary.count as i64;
+        }
+        summary
+    }
+
+    // Shared by manual and device readings so both get threshold checks,
+    // abnormal history and care plan updates
+    fn store_reading(
+        env: &Env,
+        patient_id: &Address,
+        recorder: Address,
+        measurement_time: u64,
+        vitals: VitalSigns,
+    ) -> (u64, bool) {
+        // Load existing history or create new
+        let key = DataKey::VitalsHistory(patient_id.clone());
+        let mut history: Vec<VitalReading> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+
+        let reading_id = history.len() as u64 + 1;
+        let alert =
+            Self::check_vital_thresholds(env, patient_id, reading_id, measurement_time, &vitals);
+
+        // Care plans trust this contract's auth, so only forward trusted readings
+        if Self::is_trusted_recorder(env, patient_id, &recorder) {
+            Self::notify_care_plan(env, patient_id, measurement_time, &vitals);
+        }
+
+        history.push_back(VitalReading {
+            measurement_time,
+            vitals,
+            recorder,
+        });
+        env.storage().persistent().set(&key, &history);
+
+        (reading_id, alert)
+    }
+
+    fn check_vital_thresholds(
+        env: &Env,
+        patient_id: &Address,
+        reading_id: u64,
+        measurement_time: u64,
+        vitals: &VitalSigns,
+    ) -> bool {
+        let mut alert = false;
+        for name in VITAL_METRICS.iter() {
+            let metric = Symbol::new(env, name);
+            let value = match Self::extract_vital_value(env, vitals, &metric) {
+                Some(v) => v as i64,
+                None => continue,
+            };
+
+            let key = DataKey::VitalThreshold(patient_id.clone(), metric.clone());
+            if let Some(threshold) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, VitalThreshold>(&key)
+            {
+                if value < threshold.min || value > threshold.max {
+                    alert = true;
+                    Self::record_abnormal(
+                        env,
+                        patient_id,
+                        &metric,
+                        AbnormalReading {
+                            reading_id,
+                            measurement_time,
+                            value,
+                        },
+                    );
+                    VitalOutOfRange {
+                        patient_id: patient_id.clone(),
+                        metric,
+                        value,
+                        measurement_time,
+                    }
+                    .publish(env);
+                }
+            }
+        }
+        alert
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        let stored: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Admin)
+            .ok_or(Error::Unauthorized)?;
+        if stored != *admin {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // The patient, the admin, or a provider the admin has authorized
+    fn is_trusted_recorder(env: &Env, patient_id: &Address, recorder: &Address) -> bool {
+        if recorder == patient_id {
+            return true;
+        }
+        let admin: Option<Address> = env.storage().persistent().get(&DataKey::Admin);
+        admin.as_ref() == Some(recorder)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::AuthorizedProvider(recorder.clone()))
+    }
+
+    fn notify_care_plan(
+        env: &Env,
+        patient_id: &Address,
+        measurement_time: u64,
+        vitals: &VitalSigns,
+    ) {
+        let link: CarePlanLink = match env.storage().persistent().get(&DataKey::CarePlanLink) {
+            Some(link) => link,
+            None => return,
+        };
+        if !link.enabled {
+            return;
+        }
+
+        let mut readings: Vec<(Symbol, i64)> = Vec::new(env);
+        for name in VITAL_METRICS.iter() {
+            let metric = Symbol::new(env, name);
+            if let Some(value) = Self::extract_vital_value(env, vitals, &metric) {
+                readings.push_back((metric, value as i64));
+            }
+        }
+        if readings.is_empty() {
+            return;
+        }
+
+        // A failing care plan must not block recording the reading itself
+        let care_plan = CarePlanClient::new(env, &link.care_plan);
+        let _ = care_plan.try_record_vital_progress(
+            &env.current_contract_address(),
+            patient_id,
+            &measurement_time,
+            &readings,
+        );
+    }
+
+    fn record_abnormal(env: &Env, patient_id: &Address, metric: &Symbol, reading: AbnormalReading) {
+        let key = DataKey::AbnormalReadings(patient_id.clone(), metric.clone());
+        let mut readings: Vec<AbnormalReading> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        readings.push_back(reading);
+        env.storage().persistent().set(&key, &readings);
+
+        // Kept separately so dashboards can read the total without loading the list
+        let count_key = DataKey::AbnormalCount(patient_id.clone(), metric.clone());
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        env.storage().persistent().set(&count_key, &(count + 1));
+    }
+
     fn extract_vital_value(env: &Env, vitals: &VitalSigns, vital_type: &Symbol) -> Option<u32> {
         if vital_type == &Symbol::new(env, "heart_rate") {
             return vitals.heart_rate;
@@ -1,5 +1,6 @@
 #![no_std]
 
+mod clients;
 mod contract;
 mod types;
 
@@ -7,3 +8,4 @@ mod types;
 mod test;
 
 pub use crate::contract::{PatientVitalsContract, PatientVitalsContractClient};
+pub use crate::types::*;
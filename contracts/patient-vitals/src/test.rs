@@ -2,7 +2,7 @@
 
 use super::*;
 use crate::contract::{PatientVitalsContract, PatientVitalsContractClient};
-use crate::types::{AlertThresholds, DeviceReading, Range, VitalSigns, VitalStatistics};
+use crate::types::{AlertThresholds, DeviceReading, Range, VitalSigns};
 use soroban_sdk::{testutils::Address as _, Address, Env, String, Symbol, Vec};
 
 #[test]
@@ -10,7 +10,7 @@ fn test_record_vital_signs() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, PatientVitalsContract);
+    let contract_id = env.register(PatientVitalsContract, ());
     let client = PatientVitalsContractClient::new(&env, &contract_id);
 
     let patient_id = Address::generate(&env);
@@ -28,7 +28,7 @@ fn test_record_vital_signs() {
     };
 
     let result = client.record_vital_signs(&patient_id, &provider_id, &1672531200, &vitals);
-    assert_eq!(result, 1);
+    assert_eq!(result, (1, false));
 
     // Test get trends
     let trends = client.get_vital_trends(
@@ -46,7 +46,7 @@ fn test_set_monitoring_parameters() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, PatientVitalsContract);
+    let contract_id = env.register(PatientVitalsContract, ());
     let client = PatientVitalsContractClient::new(&env, &contract_id);
 
     let patient_id = Address::generate(&env);
@@ -75,7 +75,7 @@ fn test_device_registration_and_reading() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, PatientVitalsContract);
+    let contract_id = env.register(PatientVitalsContract, ());
     let client = PatientVitalsContractClient::new(&env, &contract_id);
 
     let patient_id = Address::generate(&env);
@@ -104,7 +104,12 @@ fn test_device_registration_and_reading() {
         },
     });
 
+    // Device readings go through the same threshold checks as manual ones
+    let heart_rate = Symbol::new(&env, "heart_rate");
+    client.set_vital_threshold(&patient_id, &Address::generate(&env), &heart_rate, &50, &70);
+
     client.submit_device_reading(&device_id, &patient_id, &1672531200, &readings);
+    assert_eq!(client.get_abnormal_count(&patient_id, &heart_rate), 1);
 
     // Verify trends to see the reading was added
     let trends =
@@ -118,7 +123,7 @@ fn test_trigger_vital_alert() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, PatientVitalsContract);
+    let contract_id = env.register(PatientVitalsContract, ());
     let client = PatientVitalsContractClient::new(&env, &contract_id);
 
     let patient_id = Address::generate(&env);
@@ -137,7 +142,7 @@ fn test_calculate_vital_statistics() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, PatientVitalsContract);
+    let contract_id = env.register(PatientVitalsContract, ());
     let client = PatientVitalsContractClient::new(&env, &contract_id);
 
     let patient_id = Address::generate(&env);
@@ -170,3 +175,319 @@ fn test_calculate_vital_statistics() {
     assert_eq!(stats.max_value, 90);
     assert_eq!(stats.average_value, 85);
 }
+
+#[test]
+fn test_vital_summary_over_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let glucose = Symbol::new(&env, "blood_glucose");
+
+    let mut vitals = VitalSigns {
+        blood_pressure_systolic: None,
+        blood_pressure_diastolic: None,
+        heart_rate: None,
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: Some(95),
+        weight: None,
+    };
+    client.record_vital_signs(&patient_id, &provider_id, &1000, &vitals);
+    vitals.blood_glucose = Some(140);
+    client.record_vital_signs(&patient_id, &provider_id, &2000, &vitals);
+    vitals.blood_glucose = Some(110);
+    client.record_vital_signs(&patient_id, &provider_id, &3000, &vitals);
+    vitals.blood_glucose = Some(300);
+    client.record_vital_signs(&patient_id, &provider_id, &9000, &vitals);
+
+    let summary = client.vital_summary(&patient_id, &glucose, &1000, &3000);
+    assert_eq!(summary.count, 3);
+    assert_eq!(summary.min, 95);
+    assert_eq!(summary.max, 140);
+    // (95 + 140 + 110) / 3 = 115
+    assert_eq!(summary.avg, 115);
+
+    let empty = client.vital_summary(&patient_id, &glucose, &4000, &8000);
+    assert_eq!(empty.count, 0);
+    assert_eq!(empty.min, 0);
+    assert_eq!(empty.avg, 0);
+}
+
+#[test]
+fn test_get_latest_vital() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let heart_rate = Symbol::new(&env, "heart_rate");
+
+    assert_eq!(client.get_latest_vital(&patient_id, &heart_rate), None);
+
+    let mut vitals = VitalSigns {
+        blood_pressure_systolic: None,
+        blood_pressure_diastolic: None,
+        heart_rate: Some(70),
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: None,
+        weight: None,
+    };
+    client.record_vital_signs(&patient_id, &provider_id, &2000, &vitals);
+
+    // Recorded later but measured earlier; must not win
+    vitals.heart_rate = Some(60);
+    client.record_vital_signs(&patient_id, &provider_id, &1000, &vitals);
+
+    // A reading without the metric is skipped
+    vitals.heart_rate = None;
+    client.record_vital_signs(&patient_id, &provider_id, &3000, &vitals);
+
+    assert_eq!(
+        client.get_latest_vital(&patient_id, &heart_rate),
+        Some((2000, 70))
+    );
+}
+
+#[test]
+fn test_vital_threshold_alert() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let heart_rate = Symbol::new(&env, "heart_rate");
+
+    client.set_vital_threshold(&patient_id, &provider_id, &heart_rate, &50, &110);
+
+    let mut vitals = VitalSigns {
+        blood_pressure_systolic: Some(180),
+        blood_pressure_diastolic: None,
+        heart_rate: Some(72),
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: None,
+        weight: None,
+    };
+    // Systolic has no threshold set, so only heart rate is checked
+    let (_, alert) = client.record_vital_signs(&patient_id, &provider_id, &1000, &vitals);
+    assert!(!alert);
+
+    vitals.heart_rate = Some(130);
+    let (record_id, alert) = client.record_vital_signs(&patient_id, &provider_id, &2000, &vitals);
+    assert_eq!(record_id, 2);
+    assert!(alert);
+
+    let result = client.try_set_vital_threshold(&patient_id, &provider_id, &heart_rate, &120, &60);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+fn test_list_abnormal_readings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let systolic = Symbol::new(&env, "bp_systolic");
+
+    client.set_vital_threshold(&patient_id, &provider_id, &systolic, &90, &140);
+
+    let mut vitals = VitalSigns {
+        blood_pressure_systolic: Some(120),
+        blood_pressure_diastolic: None,
+        heart_rate: None,
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: None,
+        weight: None,
+    };
+    // Readings at t = 1000, 2000, ... 5000
+    for (i, value) in [120u32, 160, 130, 85, 150].into_iter().enumerate() {
+        vitals.blood_pressure_systolic = Some(value);
+        let time = (i as u64 + 1) * 1000;
+        client.record_vital_signs(&patient_id, &provider_id, &time, &vitals);
+    }
+
+    assert_eq!(client.get_abnormal_count(&patient_id, &systolic), 3);
+
+    let all = client.list_abnormal_readings(&patient_id, &systolic, &0, &10);
+    assert_eq!(all.len(), 3);
+    assert_eq!(all.get(0).unwrap().reading_id, 2);
+    assert_eq!(all.get(0).unwrap().value, 160);
+    assert_eq!(all.get(1).unwrap().measurement_time, 4000);
+
+    let page = client.list_abnormal_readings(&patient_id, &systolic, &2, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().value, 150);
+
+    let heart_rate = Symbol::new(&env, "heart_rate");
+    assert_eq!(client.get_abnormal_count(&patient_id, &heart_rate), 0);
+    let none = client.list_abnormal_readings(&patient_id, &heart_rate, &0, &10);
+    assert_eq!(none.len(), 0);
+}
+
+#[test]
+fn test_get_vital_trend() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let systolic = Symbol::new(&env, "bp_systolic");
+
+    let mut vitals = VitalSigns {
+        blood_pressure_systolic: Some(140),
+        blood_pressure_diastolic: None,
+        heart_rate: None,
+        temperature: None,
+        respiratory_rate: None,
+        oxygen_saturation: None,
+        blood_glucose: None,
+        weight: None,
+    };
+    client.record_vital_signs(&patient_id, &provider_id, &3000, &vitals);
+    vitals.blood_pressure_systolic = Some(150);
+    client.record_vital_signs(&patient_id, &provider_id, &1000, &vitals);
+    vitals.blood_pressure_systolic = Some(130);
+    client.record_vital_signs(&patient_id, &provider_id, &2000, &vitals);
+    vitals.blood_pressure_systolic = Some(125);
+    client.record_vital_signs(&patient_id, &provider_id, &5000, &vitals);
+    vitals.blood_pressure_systolic = None;
+    client.record_vital_signs(&patient_id, &provider_id, &2500, &vitals);
+
+    let trend = client.get_vital_trend(&patient_id, &systolic, &1000, &3000);
+    let mut expected: Vec<(u64, i64)> = Vec::new(&env);
+    expected.push_back((1000, 150));
+    expected.push_back((2000, 130));
+    expected.push_back((3000, 140));
+    assert_eq!(trend, expected);
+
+    assert_eq!(client.get_vital_trend(&patient_id, &systolic, &6000, &7000).len(), 0);
+}
+
+#[test]
+fn test_gfr_trend() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let gfr = Symbol::new(&env, "gfr");
+    client.initialize(&admin);
+    client.set_provider_authorized(&admin, &provider_id, &true);
+
+    client.record_gfr(&patient_id, &provider_id, &2000, &45);
+    client.record_gfr(&patient_id, &provider_id, &1000, &60);
+    client.record_gfr(&patient_id, &provider_id, &4000, &38);
+    assert_eq!(
+        client.try_record_gfr(&patient_id, &provider_id, &5000, &0),
+        Err(Ok(Error::InvalidParameter))
+    );
+
+    let trend = client.get_vital_trend(&patient_id, &gfr, &1000, &3000);
+    let mut expected: Vec<(u64, i64)> = Vec::new(&env);
+    expected.push_back((1000, 60));
+    expected.push_back((2000, 45));
+    assert_eq!(trend, expected);
+
+    // GFR is not part of the bedside vitals history
+    let heart_rate = Symbol::new(&env, "heart_rate");
+    let trend = client.get_vital_trend(&patient_id, &heart_rate, &0, &5000);
+    assert_eq!(trend.len(), 0);
+}
+
+#[test]
+fn test_gfr_from_outsider_is_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient_id = Address::generate(&env);
+    let provider_id = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let gfr = Symbol::new(&env, "gfr");
+    client.initialize(&admin);
+
+    assert_eq!(
+        client.try_record_gfr(&patient_id, &outsider, &1000, &15),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_provider_authorized(&outsider, &provider_id, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.record_gfr(&patient_id, &patient_id, &1000, &70);
+    client.set_provider_authorized(&admin, &provider_id, &true);
+    assert!(client.is_provider_authorized(&provider_id));
+    client.record_gfr(&patient_id, &provider_id, &2000, &20);
+    let trend = client.get_vital_trend(&patient_id, &gfr, &0, &3000);
+    assert_eq!(trend.len(), 2);
+
+    // Readings from a provider whose authorization was revoked drop out of the trend
+    client.set_provider_authorized(&admin, &provider_id, &false);
+    let trend = client.get_vital_trend(&patient_id, &gfr, &0, &3000);
+    let mut expected: Vec<(u64, i64)> = Vec::new(&env);
+    expected.push_back((1000, 70));
+    assert_eq!(trend, expected);
+}
+
+#[test]
+fn test_care_plan_link_is_admin_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PatientVitalsContract, ());
+    let client = PatientVitalsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let care_plan = Address::generate(&env);
+    assert_eq!(
+        client.try_set_care_plan_link(&admin, &care_plan, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.initialize(&admin);
+    assert_eq!(
+        client.try_initialize(&admin),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(
+        client.try_set_care_plan_link(&Address::generate(&env), &care_plan, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_care_plan_link(&admin, &care_plan, &true);
+    let link = client.get_care_plan_link().unwrap();
+    assert_eq!(link.care_plan, care_plan);
+    assert!(link.enabled);
+}
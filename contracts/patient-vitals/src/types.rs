@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, Address, String, Symbol};
+use soroban_sdk::{contracterror, contractevent, contracttype, Address, String, Symbol};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -7,6 +7,7 @@ pub enum Error {
     Unauthorized = 1,
     NotFound = 2,
     InvalidParameter = 3,
+    AlreadyInitialized = 4,
 }
 
 #[contracttype]
@@ -47,13 +48,29 @@ pub struct VitalStatistics {
     pub count: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VitalSummary {
+    pub min: i64,
+    pub max: i64,
+    pub avg: i64,
+    pub count: u32,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
+    Admin,                             // map to Address
+    CarePlanLink,                      // map to CarePlanLink
     VitalsHistory(Address),            // map to Vec<VitalReading>
     MonitoringParams(Address, Symbol), // map to MonitoringParameters
     DeviceReg(Address, String),        // map to DeviceRegistration
     VitalsAlerts(Address, Symbol),     // map to Vec<VitalAlert>
+    VitalThreshold(Address, Symbol),   // map to VitalThreshold
+    AbnormalReadings(Address, Symbol), // map to Vec<AbnormalReading>
+    AbnormalCount(Address, Symbol),    // map to u32
+    GfrHistory(Address),               // map to Vec<GfrReading>
+    AuthorizedProvider(Address),       // map to bool
 }
 
 #[contracttype]
@@ -65,6 +82,39 @@ pub struct MonitoringParameters {
     pub monitoring_frequency: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VitalThreshold {
+    pub provider_id: Address,
+    pub min: i64,
+    pub max: i64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CarePlanLink {
+    pub care_plan: Address,
+    pub enabled: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbnormalReading {
+    pub reading_id: u64,
+    pub measurement_time: u64,
+    pub value: i64,
+}
+
+/// Emitted when a recorded vital falls outside the patient's threshold.
+#[contractevent]
+pub struct VitalOutOfRange {
+    #[topic]
+    pub patient_id: Address,
+    pub metric: Symbol,
+    pub value: i64,
+    pub measurement_time: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DeviceRegistration {
@@ -89,6 +139,16 @@ pub struct VitalReading {
     pub recorder: Address, // patient, provider, or device
 }
 
+// Renal function is charted from labs rather than bedside vitals, so it is
+// kept apart from VitalSigns
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GfrReading {
+    pub measurement_time: u64,
+    pub gfr: u32, // eGFR in mL/min/1.73m²
+    pub recorder: Address,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DeviceReading {